@@ -0,0 +1,581 @@
+use crate::binding;
+use crate::error::{Error, Result};
+use crate::options::Options;
+use crate::segment::{self, LitStr, Segment};
+use proc_macro2::{Delimiter, Group, Literal, Spacing, Span, TokenStream, TokenTree};
+use std::char;
+use std::iter;
+use std::str::FromStr;
+
+// Attributes are expanded according to the shape of their meta item:
+//
+//     $path
+//     $path = $value
+//     $path ( $($nested),* )
+//
+// where each nested element is itself a meta item or a literal. A paste in
+// place of an identifier, such as in the path, produces an identifier. Values
+// made of a paste or of more than one string literal, identifier or modifier
+// are pasted together into a single string literal, at any depth of nesting.
+//
+// The `#[paste(...)]` helper attributes of `#[derive(PasteNames)]` are left for
+// the derive to paste.
+pub fn expand_attr(
+    attr: TokenStream,
+    span: Span,
+    contains_paste: &mut bool,
+    options: &Options,
+) -> Result<TokenStream> {
+    let meta: Vec<TokenTree> = attr.into_iter().collect();
+    if let [TokenTree::Ident(ident), TokenTree::Group(group)] = meta.as_slice() {
+        if ident == "paste" && group.delimiter() == Delimiter::Parenthesis {
+            return Ok(meta.into_iter().collect());
+        }
+    }
+    expand_meta(meta, span, contains_paste, options)
+}
+
+fn expand_meta(
+    meta: Vec<TokenTree>,
+    span: Span,
+    contains_paste: &mut bool,
+    options: &Options,
+) -> Result<TokenStream> {
+    let path_len = path_len(&meta);
+    if path_len == 0 {
+        // Not a meta item, for example a literal in a list.
+        return expand_tokens(meta, contains_paste, options);
+    }
+
+    let path = expand_tokens(meta[..path_len].to_vec(), contains_paste, options)?;
+    let ident = match &meta[0] {
+        TokenTree::Ident(ident) if path_len == 1 => ident.to_string(),
+        _ => String::new(),
+    };
+    match meta.get(path_len) {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+            let interpolate = options.interpolate_docs && ident == "doc";
+            let as_string = false;
+            let value = &meta[path_len + 1..];
+            let mut expanded = path;
+            expanded.extend(iter::once(meta[path_len].clone()));
+            expanded.extend(expand_value(
+                value,
+                span,
+                interpolate,
+                as_string,
+                contains_paste,
+                options,
+            )?);
+            Ok(expanded)
+        }
+        Some(TokenTree::Group(group))
+            if group.delimiter() == Delimiter::Parenthesis && meta.len() == path_len + 1 =>
+        {
+            let mut group_contains_paste = false;
+            let nested = match ident.as_str() {
+                "cfg" => expand_list(
+                    group,
+                    &mut group_contains_paste,
+                    |_, predicate, contains_paste| {
+                        expand_predicate(predicate, group.span(), contains_paste, options)
+                    },
+                )?,
+                "cfg_attr" => expand_cfg_attr(group, span, &mut group_contains_paste, options)?,
+                _ => expand_list(
+                    group,
+                    &mut group_contains_paste,
+                    |_, nested, contains_paste| {
+                        expand_meta(nested, group.span(), contains_paste, options)
+                    },
+                )?,
+            };
+            let path: Vec<TokenTree> = path.into_iter().collect();
+            Ok(rebuild_list(
+                &path,
+                group,
+                nested,
+                group_contains_paste,
+                contains_paste,
+            ))
+        }
+        _ => {
+            let mut expanded = path;
+            expanded.extend(expand_tokens(
+                meta[path_len..].to_vec(),
+                contains_paste,
+                options,
+            )?);
+            Ok(expanded)
+        }
+    }
+}
+
+fn expand_value(
+    value: &[TokenTree],
+    span: Span,
+    interpolate: bool,
+    as_string: bool,
+    contains_paste: &mut bool,
+    options: &Options,
+) -> Result<TokenStream> {
+    let mut has_paste = false;
+    let mut errors = None;
+    let mut tokens = Vec::new();
+    for tt in value {
+        match tt {
+            TokenTree::Literal(lit) if interpolate => match interpolate_doc(lit, options)? {
+                Some(lit) => {
+                    tokens.push(TokenTree::Literal(lit));
+                    has_paste = true;
+                }
+                None => tokens.push(tt.clone()),
+            },
+            TokenTree::Group(group)
+                if group.delimiter() == Delimiter::Bracket
+                    && crate::is_paste_operation(&group.stream()) =>
+            {
+                match crate::paste_bracket(group.stream(), group.span(), as_string, options) {
+                    Ok(pasted) => tokens.extend(pasted),
+                    Err(err) => Error::accumulate(&mut errors, err),
+                }
+                has_paste = true;
+            }
+            _ => tokens.push(tt.clone()),
+        }
+    }
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    if is_pasted_value(&tokens)? {
+        *contains_paste = true;
+        return paste_string(tokens, span, options);
+    }
+    if has_paste {
+        *contains_paste = true;
+        return Ok(tokens.into_iter().collect());
+    }
+    expand_tokens(tokens, contains_paste, options)
+}
+
+// Pastes in any other position are expanded the same way as outside of an
+// attribute.
+fn expand_tokens(
+    tokens: Vec<TokenTree>,
+    contains_paste: &mut bool,
+    options: &Options,
+) -> Result<TokenStream> {
+    let flatten_single_interpolation = false;
+    crate::expand_tokens(
+        tokens.into_iter().collect(),
+        contains_paste,
+        flatten_single_interpolation,
+        options,
+    )
+}
+
+fn expand_list<F>(
+    group: &Group,
+    contains_paste: &mut bool,
+    mut expand_element: F,
+) -> Result<TokenStream>
+where
+    F: FnMut(usize, Vec<TokenTree>, &mut bool) -> Result<TokenStream>,
+{
+    let mut expanded = TokenStream::new();
+    let mut errors = None;
+    let mut nested = Vec::new();
+    let mut index = 0;
+    for tt in group.stream() {
+        match &tt {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                let nested = std::mem::take(&mut nested);
+                match expand_element(index, nested, contains_paste) {
+                    Ok(element) => expanded.extend(element),
+                    Err(err) => Error::accumulate(&mut errors, err),
+                }
+                expanded.extend(iter::once(tt));
+                index += 1;
+            }
+            _ => nested.push(tt),
+        }
+    }
+    if !nested.is_empty() {
+        match expand_element(index, nested, contains_paste) {
+            Ok(element) => expanded.extend(element),
+            Err(err) => Error::accumulate(&mut errors, err),
+        }
+    }
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(expanded),
+    }
+}
+
+// #[cfg_attr($predicate, $($attr),*)]
+//
+// The predicate is expanded by the rules of `#[cfg(...)]`, and each of the
+// attributes as if it were written as a standalone `#[$attr]`.
+fn expand_cfg_attr(
+    group: &Group,
+    span: Span,
+    contains_paste: &mut bool,
+    options: &Options,
+) -> Result<TokenStream> {
+    expand_list(group, contains_paste, |index, element, contains_paste| {
+        if index == 0 {
+            expand_predicate(element, group.span(), contains_paste, options)
+        } else {
+            expand_meta(element, span, contains_paste, options)
+        }
+    })
+}
+
+// $ident
+// $ident = $value
+// all($($predicate),*), any($($predicate),*), not($predicate)
+fn expand_predicate(
+    predicate: Vec<TokenTree>,
+    span: Span,
+    contains_paste: &mut bool,
+    options: &Options,
+) -> Result<TokenStream> {
+    let op = match predicate.first() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return expand_tokens(predicate, contains_paste, options),
+    };
+    match predicate.get(1) {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+            let mut expanded: TokenStream = predicate[..2].iter().cloned().collect();
+            let interpolate = false;
+            // The value of a predicate is always a string literal.
+            let as_string = true;
+            let value = &predicate[2..];
+            expanded.extend(expand_value(
+                value,
+                span,
+                interpolate,
+                as_string,
+                contains_paste,
+                options,
+            )?);
+            Ok(expanded)
+        }
+        Some(TokenTree::Group(group))
+            if group.delimiter() == Delimiter::Parenthesis
+                && predicate.len() == 2
+                && (op == "all" || op == "any" || op == "not") =>
+        {
+            let mut group_contains_paste = false;
+            let nested = expand_list(
+                group,
+                &mut group_contains_paste,
+                |_, nested, contains_paste| {
+                    expand_predicate(nested, group.span(), contains_paste, options)
+                },
+            )?;
+            Ok(rebuild_list(
+                &predicate[..1],
+                group,
+                nested,
+                group_contains_paste,
+                contains_paste,
+            ))
+        }
+        _ => expand_tokens(predicate, contains_paste, options),
+    }
+}
+
+fn rebuild_list(
+    path: &[TokenTree],
+    group: &Group,
+    nested: TokenStream,
+    group_contains_paste: bool,
+    contains_paste: &mut bool,
+) -> TokenStream {
+    let mut expanded: TokenStream = path.iter().cloned().collect();
+    if group_contains_paste {
+        *contains_paste = true;
+        let mut nested = Group::new(Delimiter::Parenthesis, nested);
+        nested.set_span(group.span());
+        expanded.extend(iter::once(TokenTree::Group(nested)));
+    } else {
+        expanded.extend(iter::once(TokenTree::Group(group.clone())));
+    }
+    expanded
+}
+
+// Doc comments reach the macro as `#[doc = "..."]` with the whole comment in
+// a single string literal. With the `interpolate_docs` option, each `[<...>]`
+// found in the contents of such a literal is pasted in place.
+fn interpolate_doc(lit: &Literal, options: &Options) -> Result<Option<Literal>> {
+    let repr = lit.to_string();
+    if !repr.starts_with('"') || !repr.contains("[<") {
+        return Ok(None);
+    }
+
+    let mut contents = String::new();
+    let mut rest = &repr[1..repr.len() - 1];
+    while let Some(begin) = rest.find("[<") {
+        let end = match rest[begin..].find(">]") {
+            Some(len) => begin + len + 2,
+            None => {
+                return Err(Error::new(
+                    lit.span(),
+                    "unterminated `[<` in doc comment, expected `>]`",
+                ));
+            }
+        };
+        contents.push_str(&rest[..begin]);
+        contents.push_str(&paste_in_doc(
+            &unescape(&rest[begin..end]),
+            lit.span(),
+            options,
+        )?);
+        rest = &rest[end..];
+    }
+    contents.push_str(rest);
+
+    let mut interpolated = match TokenStream::from_str(&format!("\"{}\"", contents))
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap()
+    {
+        TokenTree::Literal(interpolated) => interpolated,
+        _ => unreachable!(),
+    };
+    interpolated.set_span(lit.span());
+    Ok(Some(interpolated))
+}
+
+// Pastes the `[<...>]` taken from a doc comment, returning the result escaped
+// as the contents of a string literal.
+fn paste_in_doc(marker: &str, span: Span, options: &Options) -> Result<String> {
+    let group = match TokenStream::from_str(marker).map(|tokens| respan(tokens, span)) {
+        Ok(tokens) => match tokens.into_iter().next() {
+            Some(TokenTree::Group(group)) => group,
+            _ => unreachable!(),
+        },
+        Err(_) => return Err(Error::new(span, "invalid tokens in `[< ... >]`")),
+    };
+    // Only the `$name` of a binding is substituted here.
+    let content = binding::substitute(group.stream(), &options.bindings);
+    if content.to_string().contains('$') {
+        return Err(Error::new(
+            span,
+            "macro_rules does not substitute `$` variables inside doc comments, use `#[doc = ...]` instead",
+        ));
+    }
+    let pasted = crate::paste_bracket(content, span, true, options)?;
+    let repr = pasted.to_string();
+    if repr.starts_with('"') {
+        Ok(repr[1..repr.len() - 1].to_owned())
+    } else {
+        Ok(repr)
+    }
+}
+
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    let mut respanned = TokenStream::new();
+    for mut tt in tokens {
+        if let TokenTree::Group(group) = &tt {
+            let stream = respan(group.stream(), span);
+            tt = TokenTree::Group(Group::new(group.delimiter(), stream));
+        }
+        tt.set_span(span);
+        respanned.extend(iter::once(tt));
+    }
+    respanned
+}
+
+// Inverse of the escaping applied to the contents of doc comments.
+fn unescape(escaped: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = escaped.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            unescaped.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some('0') => unescaped.push('\0'),
+            Some('u') => {
+                let hex: String = chars.by_ref().skip(1).take_while(|&ch| ch != '}').collect();
+                if let Some(ch) = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    unescaped.push(ch);
+                }
+            }
+            Some(ch) => unescaped.push(ch),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+// Number of tokens making up `$(::)? $ident $(:: $ident)*` at the start of the
+// meta item, or 0 if it does not start with a path.
+fn path_len(meta: &[TokenTree]) -> usize {
+    let mut len = 0;
+    if is_path_sep(meta, len) {
+        len += 2;
+    }
+    loop {
+        match meta.get(len) {
+            Some(TokenTree::Ident(_)) => len += 1,
+            Some(TokenTree::Group(group))
+                if group.delimiter() == Delimiter::Bracket
+                    && crate::is_paste_operation(&group.stream()) =>
+            {
+                len += 1;
+            }
+            _ => return 0,
+        }
+        if is_path_sep(meta, len) {
+            len += 2;
+        } else {
+            return len;
+        }
+    }
+}
+
+fn is_path_sep(meta: &[TokenTree], i: usize) -> bool {
+    match (meta.get(i), meta.get(i + 1)) {
+        (Some(TokenTree::Punct(fst)), Some(TokenTree::Punct(snd))) => {
+            fst.as_char() == ':' && fst.spacing() == Spacing::Joint && snd.as_char() == ':'
+        }
+        _ => false,
+    }
+}
+
+// Whether the value of a name-value attribute is something to paste. A value
+// with a string literal mixed in among tokens that cannot be pasted is an
+// error, rather than something to silently pass through.
+fn is_pasted_value(value: &[TokenTree]) -> Result<bool> {
+    if value.len() < 2 {
+        return Ok(false);
+    }
+    let mut unsupported = None;
+    let mut i = 0;
+    while i < value.len() {
+        if is_env_macro(&value[i..]) {
+            i += 3;
+        } else if is_stringlike(&value[i]) {
+            i += 1;
+        } else {
+            unsupported = Some(&value[i]);
+            break;
+        }
+    }
+    let unsupported = match unsupported {
+        Some(unsupported) => unsupported,
+        None => return Ok(true),
+    };
+    if value.iter().any(is_string_literal) {
+        return Err(Error::new(
+            unsupported.span(),
+            "unsupported token in attribute value, expected string literal, identifier or `:modifier`",
+        ));
+    }
+    Ok(false)
+}
+
+fn paste_string(value: Vec<TokenTree>, span: Span, options: &Options) -> Result<TokenStream> {
+    let value: TokenStream = value.into_iter().collect();
+    let mut tokens = value.into_iter().peekable();
+    let mut segments = segment::parse_raw(&mut tokens)?;
+    unquote_segments(&mut segments)?;
+    options.apply_acronyms(&mut segments);
+
+    let contents = segment::paste(&segments)?;
+    crate::escaped_to_string(&contents, span)
+}
+
+// Replaces string literal segments by their contents, escaped such that they
+// can be pasted into the contents of a new string literal. The value of an
+// `env!("...")` is escaped the same way, and kept as is rather than adjusted
+// to fit in an identifier.
+pub fn unquote_segments(segments: &mut [Segment]) -> Result<()> {
+    for segment in segments {
+        if let Segment::Env(var) = segment {
+            let resolved = segment::resolve_env(var)?;
+            *segment = Segment::String(LitStr {
+                value: resolved.chars().flat_map(char::escape_default).collect(),
+                span: var.span,
+            });
+        } else if let Segment::String(string) = segment {
+            if let Some(open_quote) = string.value.find('"') {
+                if open_quote == 0 {
+                    string.value.truncate(string.value.len() - 1);
+                    string.value.remove(0);
+                } else {
+                    let begin = open_quote + 1;
+                    let end = string.value.rfind('"').unwrap();
+                    let raw_string = std::mem::take(&mut string.value);
+                    for ch in raw_string[begin..end].chars() {
+                        string.value.extend(ch.escape_default());
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_stringlike(token: &TokenTree) -> bool {
+    match token {
+        TokenTree::Ident(_) => true,
+        TokenTree::Literal(literal) => {
+            let repr = literal.to_string();
+            !repr.starts_with('b') && !repr.starts_with('\'')
+        }
+        TokenTree::Group(group) => {
+            if group.delimiter() != Delimiter::None {
+                return false;
+            }
+            let mut inner = group.stream().into_iter();
+            match inner.next() {
+                Some(first) => inner.next().is_none() && is_stringlike(&first),
+                None => false,
+            }
+        }
+        TokenTree::Punct(punct) => {
+            punct.as_char() == '\'' || punct.as_char() == ':' && punct.spacing() == Spacing::Alone
+        }
+    }
+}
+
+// env!("...")
+fn is_env_macro(tokens: &[TokenTree]) -> bool {
+    match (tokens.first(), tokens.get(1), tokens.get(2)) {
+        (
+            Some(TokenTree::Ident(ident)),
+            Some(TokenTree::Punct(punct)),
+            Some(TokenTree::Group(group)),
+        ) => {
+            *ident == "env" && punct.as_char() == '!' && group.delimiter() == Delimiter::Parenthesis
+        }
+        _ => false,
+    }
+}
+
+fn is_string_literal(token: &TokenTree) -> bool {
+    match token {
+        TokenTree::Literal(literal) => {
+            let repr = literal.to_string();
+            repr.starts_with('"') || repr.starts_with('r')
+        }
+        TokenTree::Group(group) if group.delimiter() == Delimiter::None => {
+            let mut inner = group.stream().into_iter();
+            match inner.next() {
+                Some(first) => inner.next().is_none() && is_string_literal(&first),
+                None => false,
+            }
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,63 @@
+use crate::error::Result;
+use crate::options::Options;
+use proc_macro2::{Group, Ident, TokenStream, TokenTree};
+use std::mem;
+
+// Expands the value of each `#![paste(let name = ...)]` binding, in order, so
+// that the value of a binding may refer to the bindings before it.
+pub(crate) fn evaluate(options: &mut Options) -> Result<()> {
+    let bindings = mem::take(&mut options.bindings);
+    for (name, value) in bindings {
+        let value = substitute(value, &options.bindings);
+        let mut contains_paste = false;
+        let flatten_single_interpolation = true;
+        let value = crate::expand_tokens(
+            value,
+            &mut contains_paste,
+            flatten_single_interpolation,
+            options,
+        )?;
+        options.bindings.push((name, value));
+    }
+    Ok(())
+}
+
+// Replaces every `$name` of a binding by its value. Plain identifiers are left
+// alone even if they have the same name, such as the name of a modifier in
+// `[<x:first>]` or an identifier belonging to the caller.
+pub(crate) fn substitute(tokens: TokenStream, bindings: &[(Ident, TokenStream)]) -> TokenStream {
+    if bindings.is_empty() {
+        return tokens;
+    }
+    let mut substituted = TokenStream::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == '$' => {
+                let value = match tokens.peek() {
+                    Some(TokenTree::Ident(ident)) => bindings
+                        .iter()
+                        .rev()
+                        .find(|(name, _)| name == ident)
+                        .map(|(_name, value)| value.clone()),
+                    _ => None,
+                };
+                match value {
+                    Some(value) => {
+                        tokens.next();
+                        substituted.extend(value);
+                    }
+                    None => substituted.extend(Some(TokenTree::Punct(punct))),
+                }
+            }
+            TokenTree::Group(group) => {
+                let stream = substitute(group.stream(), bindings);
+                let mut sub = Group::new(group.delimiter(), stream);
+                sub.set_span(group.span());
+                substituted.extend(Some(TokenTree::Group(sub)));
+            }
+            other => substituted.extend(Some(other)),
+        }
+    }
+    substituted
+}
@@ -0,0 +1,360 @@
+use crate::error::{Error, Result};
+use crate::segment;
+use proc_macro2::{
+    token_stream, Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree,
+};
+use std::iter::{self, FromIterator, Peekable};
+
+pub fn derive_names(input: TokenStream) -> Result<TokenStream> {
+    let mut tokens = input.into_iter().peekable();
+    let mut attrs = Vec::new();
+
+    // Outer attributes, keeping only `#[paste(...)]`.
+    while let Some(TokenTree::Punct(punct)) = tokens.peek() {
+        if punct.as_char() != '#' {
+            break;
+        }
+        tokens.next().unwrap();
+        if let Some(TokenTree::Group(group)) = tokens.next() {
+            let mut inner = group.stream().into_iter();
+            match (inner.next(), inner.next(), inner.next()) {
+                (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(args)), None)
+                    if ident == "paste" && args.delimiter() == Delimiter::Parenthesis =>
+                {
+                    attrs.push(args);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Visibility.
+    if let Some(TokenTree::Ident(ident)) = tokens.peek() {
+        if *ident == "pub" {
+            tokens.next().unwrap();
+            if let Some(TokenTree::Group(group)) = tokens.peek() {
+                if group.delimiter() == Delimiter::Parenthesis {
+                    tokens.next().unwrap();
+                }
+            }
+        }
+    }
+
+    match tokens.next() {
+        Some(TokenTree::Ident(ref ident))
+            if *ident == "struct" || *ident == "enum" || *ident == "union" => {}
+        Some(wrong) => return Err(Error::new(wrong.span(), "expected struct, enum or union")),
+        None => return Err(Error::new(Span::call_site(), "unexpected end of input")),
+    }
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident,
+        Some(wrong) => return Err(Error::new(wrong.span(), "expected identifier")),
+        None => return Err(Error::new(Span::call_site(), "unexpected end of input")),
+    };
+
+    let params = parse_generics(&mut tokens);
+    let where_clause = parse_where_clause(&mut tokens);
+
+    let mut consts = TokenStream::new();
+    for attr in attrs {
+        consts.extend(parse_consts(&attr, &name)?);
+    }
+    if consts.is_empty() {
+        return Ok(TokenStream::new());
+    }
+
+    let mut impl_generics = TokenStream::new();
+    let mut ty_generics = TokenStream::new();
+    if !params.is_empty() {
+        let mut impl_params = Vec::new();
+        let mut ty_params = Vec::new();
+        for param in params {
+            let (impl_param, ty_param) = split_generic_param(param);
+            impl_params.push(impl_param);
+            ty_params.push(ty_param);
+        }
+        impl_generics = angle_bracketed(impl_params);
+        ty_generics = angle_bracketed(ty_params);
+    }
+
+    // impl $impl_generics $name $ty_generics $where_clause { $consts }
+    let mut expanded = TokenStream::new();
+    expanded.extend(iter::once(TokenTree::Ident(Ident::new(
+        "impl",
+        Span::call_site(),
+    ))));
+    expanded.extend(impl_generics);
+    expanded.extend(iter::once(TokenTree::Ident(name)));
+    expanded.extend(ty_generics);
+    expanded.extend(where_clause);
+    expanded.extend(iter::once(TokenTree::Group(Group::new(
+        Delimiter::Brace,
+        consts,
+    ))));
+    Ok(expanded)
+}
+
+// The tokens of each generic parameter between `<` and `>`, if any.
+fn parse_generics(tokens: &mut Peekable<token_stream::IntoIter>) -> Vec<Vec<TokenTree>> {
+    let mut params = Vec::new();
+    match tokens.peek() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {}
+        _ => return params,
+    }
+    tokens.next().unwrap();
+
+    let mut depth = 0;
+    let mut param = Vec::new();
+    let mut prev_dash = false;
+    for tt in tokens.by_ref() {
+        if let TokenTree::Punct(punct) = &tt {
+            let is_arrow = prev_dash;
+            prev_dash = punct.as_char() == '-' && punct.spacing() == Spacing::Joint;
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' if is_arrow => {}
+                '>' if depth == 0 => break,
+                '>' => depth -= 1,
+                ',' if depth == 0 => {
+                    params.push(param);
+                    param = Vec::new();
+                    continue;
+                }
+                _ => {}
+            }
+        } else {
+            prev_dash = false;
+        }
+        param.push(tt);
+    }
+    if !param.is_empty() {
+        params.push(param);
+    }
+    params
+}
+
+// Everything from `where` up to the body or the terminating semicolon.
+fn parse_where_clause(tokens: &mut Peekable<token_stream::IntoIter>) -> TokenStream {
+    let mut where_clause = TokenStream::new();
+    let mut in_where_clause = false;
+    for tt in tokens {
+        match &tt {
+            TokenTree::Ident(ident) if *ident == "where" => in_where_clause = true,
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => break,
+            TokenTree::Punct(punct) if punct.as_char() == ';' => break,
+            _ => {}
+        }
+        if in_where_clause {
+            where_clause.extend(iter::once(tt));
+        }
+    }
+    where_clause
+}
+
+// Splits `T: Bound = Default` into `T: Bound` for the impl and `T` for the
+// type.
+fn split_generic_param(param: Vec<TokenTree>) -> (Vec<TokenTree>, Vec<TokenTree>) {
+    let mut tokens = param.into_iter().peekable();
+
+    // Attributes on the generic parameter, e.g. `#[cfg(...)]`.
+    while let Some(TokenTree::Punct(punct)) = tokens.peek() {
+        if punct.as_char() != '#' {
+            break;
+        }
+        tokens.next().unwrap();
+        tokens.next();
+    }
+
+    let mut impl_param = Vec::new();
+    let mut ty_param = Vec::new();
+    let mut depth = 0;
+    let mut prev_dash = false;
+    for tt in tokens {
+        let is_arrow = prev_dash;
+        prev_dash = false;
+        match &tt {
+            TokenTree::Punct(punct) => match punct.as_char() {
+                '-' => prev_dash = punct.spacing() == Spacing::Joint,
+                '<' => depth += 1,
+                '>' if is_arrow => {}
+                '>' => depth -= 1,
+                '=' if depth == 0 => break,
+                _ => {}
+            },
+            TokenTree::Ident(ident) if *ident == "const" && impl_param.is_empty() => {
+                impl_param.push(tt);
+                continue;
+            }
+            _ => {}
+        }
+        let is_name = ty_param.is_empty()
+            || ty_param.len() == 1
+                && match &ty_param[0] {
+                    TokenTree::Punct(punct) => punct.as_char() == '\'',
+                    _ => false,
+                };
+        if is_name {
+            ty_param.push(tt.clone());
+        }
+        impl_param.push(tt);
+    }
+    (impl_param, ty_param)
+}
+
+fn angle_bracketed(params: Vec<Vec<TokenTree>>) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    tokens.extend(iter::once(TokenTree::Punct(Punct::new(
+        '<',
+        Spacing::Alone,
+    ))));
+    for param in params {
+        tokens.extend(param);
+        tokens.extend(iter::once(TokenTree::Punct(Punct::new(
+            ',',
+            Spacing::Alone,
+        ))));
+    }
+    tokens.extend(iter::once(TokenTree::Punct(Punct::new(
+        '>',
+        Spacing::Alone,
+    ))));
+    tokens
+}
+
+// #[paste($vis const $NAME = [< ... >], ...)]
+fn parse_consts(attr: &Group, name: &Ident) -> Result<TokenStream> {
+    let mut consts = TokenStream::new();
+    let mut tokens = attr.stream().into_iter().peekable();
+    while tokens.peek().is_some() {
+        let mut vis = Vec::new();
+        if let Some(TokenTree::Ident(ident)) = tokens.peek() {
+            if *ident == "pub" {
+                vis.push(tokens.next().unwrap());
+                if let Some(TokenTree::Group(group)) = tokens.peek() {
+                    if group.delimiter() == Delimiter::Parenthesis {
+                        vis.push(tokens.next().unwrap());
+                    }
+                }
+            }
+        }
+
+        let const_token = match tokens.next() {
+            Some(TokenTree::Ident(ident)) if ident == "const" => ident,
+            Some(wrong) => return Err(Error::new(wrong.span(), "expected `const`")),
+            None => return Err(Error::new(attr.span(), "expected `const`")),
+        };
+
+        let const_name = match tokens.next() {
+            Some(TokenTree::Ident(ident)) => ident,
+            Some(wrong) => return Err(Error::new(wrong.span(), "expected identifier")),
+            None => {
+                return Err(Error::new(
+                    const_token.span(),
+                    "expected identifier after `const`",
+                ))
+            }
+        };
+
+        match tokens.next() {
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == '=' => {}
+            Some(wrong) => return Err(Error::new(wrong.span(), "expected `=`")),
+            None => return Err(Error::new(const_name.span(), "expected `=`")),
+        }
+
+        let bracket = match tokens.next() {
+            Some(TokenTree::Group(group))
+                if group.delimiter() == Delimiter::Bracket
+                    && crate::is_paste_operation(&group.stream()) =>
+            {
+                group
+            }
+            Some(wrong) => return Err(Error::new(wrong.span(), "expected `[< ... >]`")),
+            None => return Err(Error::new(const_name.span(), "expected `[< ... >]`")),
+        };
+
+        let content = replace_self(bracket.stream(), name);
+        reject_mode(&content)?;
+        let (_output, segments) = crate::parse_bracket_as_segments(content, bracket.span())?;
+        let value = segment::paste(&segments)?;
+
+        // $vis const $NAME: &'static str = $value;
+        consts.extend(vis);
+        consts.extend(vec![
+            TokenTree::Ident(const_token),
+            TokenTree::Ident(const_name),
+            TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+            TokenTree::Punct(Punct::new('&', Spacing::Alone)),
+            TokenTree::Punct(Punct::new('\'', Spacing::Joint)),
+            TokenTree::Ident(Ident::new("static", Span::call_site())),
+            TokenTree::Ident(Ident::new("str", Span::call_site())),
+            TokenTree::Punct(Punct::new('=', Spacing::Alone)),
+            TokenTree::Literal({
+                let mut literal = Literal::string(&value);
+                literal.set_span(bracket.span());
+                literal
+            }),
+            TokenTree::Punct(Punct::new(';', Spacing::Alone)),
+        ]);
+
+        match tokens.next() {
+            None => {}
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == ',' => {}
+            Some(wrong) => return Err(Error::new(wrong.span(), "expected `,`")),
+        }
+    }
+    Ok(consts)
+}
+
+// The constants hold the pasted string itself, so `[<@fmt ...>]` and the other
+// `@` modes of `paste!` have nothing to apply to.
+fn reject_mode(content: &TokenStream) -> Result<()> {
+    let mut tokens = content.clone().into_iter().peekable();
+    tokens.next();
+    let is_mode = matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '@')
+        && !segment::starts_with_function(&tokens);
+    if !is_mode {
+        return Ok(());
+    }
+
+    let at = tokens.next().unwrap();
+    match tokens.next() {
+        Some(TokenTree::Ident(mode)) => Err(Error::new2(
+            at.span(),
+            mode.span(),
+            &format!("`@{}` is not supported in #[paste(const ...)]", mode),
+        )),
+        _ => Err(Error::new(
+            at.span(),
+            "`@` modes are not supported in #[paste(const ...)]",
+        )),
+    }
+}
+
+// Both `$Self` and `Self` refer to the type deriving PasteNames.
+fn replace_self(input: TokenStream, name: &Ident) -> TokenStream {
+    let mut tokens = input.into_iter().peekable();
+    let mut output = Vec::new();
+    while let Some(tt) = tokens.next() {
+        let is_dollar = match &tt {
+            TokenTree::Punct(punct) => punct.as_char() == '$',
+            _ => false,
+        };
+        if is_dollar {
+            if let Some(TokenTree::Ident(ident)) = tokens.peek() {
+                if *ident == "Self" {
+                    continue;
+                }
+            }
+        }
+        match tt {
+            TokenTree::Ident(ref ident) if *ident == "Self" => {
+                let mut name = name.clone();
+                name.set_span(ident.span());
+                output.push(TokenTree::Ident(name));
+            }
+            tt => output.push(tt),
+        }
+    }
+    TokenStream::from_iter(output)
+}
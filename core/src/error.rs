@@ -0,0 +1,98 @@
+use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use std::fmt::{self, Debug, Display};
+use std::iter::FromIterator;
+
+/// The result of expanding pasted tokens.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// One or more errors from expanding pasted tokens.
+pub struct Error {
+    messages: Vec<Message>,
+}
+
+struct Message {
+    begin: Span,
+    end: Span,
+    msg: String,
+}
+
+impl Error {
+    pub(crate) fn new(span: Span, msg: &str) -> Self {
+        Self::new2(span, span, msg)
+    }
+
+    pub(crate) fn new2(begin: Span, end: Span, msg: &str) -> Self {
+        Error {
+            messages: vec![Message {
+                begin,
+                end,
+                msg: msg.to_owned(),
+            }],
+        }
+    }
+
+    // Adds the messages of `err` to the errors accumulated so far.
+    pub(crate) fn accumulate(errors: &mut Option<Error>, err: Error) {
+        match errors {
+            Some(errors) => errors.messages.extend(err.messages),
+            None => *errors = Some(err),
+        }
+    }
+
+    /// Renders every error as a `compile_error!` invocation at its span.
+    pub fn to_compile_error(&self) -> TokenStream {
+        let mut tokens = TokenStream::new();
+        for message in &self.messages {
+            tokens.extend(message.to_compile_error());
+        }
+        tokens
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (i, message) in self.messages.iter().enumerate() {
+            if i > 0 {
+                formatter.write_str("\n")?;
+            }
+            formatter.write_str(&message.msg)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_list()
+            .entries(self.messages.iter().map(|message| &message.msg))
+            .finish()
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Message {
+    fn to_compile_error(&self) -> TokenStream {
+        // compile_error! { $msg }
+        TokenStream::from_iter(vec![
+            TokenTree::Ident(Ident::new("compile_error", self.begin)),
+            TokenTree::Punct({
+                let mut punct = Punct::new('!', Spacing::Alone);
+                punct.set_span(self.begin);
+                punct
+            }),
+            TokenTree::Group({
+                let mut group = Group::new(Delimiter::Brace, {
+                    TokenStream::from_iter(vec![TokenTree::Literal({
+                        let mut string = Literal::string(&self.msg);
+                        string.set_span(self.end);
+                        string
+                    })])
+                });
+                group.set_span(self.end);
+                group
+            }),
+        ])
+    }
+}
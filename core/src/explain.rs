@@ -0,0 +1,68 @@
+use crate::error::Error;
+use crate::segment::{self, Segment};
+use proc_macro2::{Span, TokenStream};
+use std::cell::RefCell;
+
+// Reports collected by `paste::explain!`, one for each `[< ... >]` in the
+// order they were expanded.
+#[derive(Default)]
+pub struct Explain {
+    reports: RefCell<Vec<(Span, String)>>,
+}
+
+impl Explain {
+    pub fn record(&self, span: Span, mode: &str, lines: &[&[Segment]], pasted: &TokenStream) {
+        let mut report = vec![format!("[< ... >] pastes to `{}`", pasted)];
+        if !mode.is_empty() {
+            report.push(format!("  mode: {}", mode));
+        }
+        for (i, segments) in lines.iter().enumerate() {
+            if lines.len() > 1 {
+                report.push(format!("  line {}:", i + 1));
+            }
+            describe(&mut report, segments);
+        }
+        self.reports.borrow_mut().push((span, report.join("\n")));
+    }
+
+    pub fn to_error(&self) -> Option<Error> {
+        let mut errors = None;
+        for (span, report) in self.reports.borrow().iter() {
+            Error::accumulate(&mut errors, Error::new(*span, report));
+        }
+        errors
+    }
+}
+
+// One line per segment, followed by one line per modifier applied to it.
+fn describe(report: &mut Vec<String>, segments: &[Segment]) {
+    let mut is_lifetime = false;
+    let mut steps = Vec::new();
+    if segment::evaluate_traced(segments, &mut is_lifetime, &mut steps).is_err() {
+        return;
+    }
+    let mut steps = steps.iter();
+    for segment in segments {
+        match segment {
+            Segment::String(string) => report.push(format!("  segment `{}`", string.value)),
+            Segment::Apostrophe(_) => report.push("  lifetime `'`".to_owned()),
+            Segment::Env(var) => {
+                let resolved = std::env::var(&var.value).unwrap_or_default();
+                let resolved = resolved.replace('-', "_");
+                report.push(format!("  segment env!({:?}) = `{}`", var.value, resolved));
+            }
+            Segment::Modifier(..) => {
+                if let Some(step) = steps.next() {
+                    let args = match &step.args {
+                        Some(args) => format!("({})", args.split_whitespace().collect::<String>()),
+                        None => String::new(),
+                    };
+                    report.push(format!(
+                        "    :{}{} `{}` -> `{}`",
+                        step.modifier, args, step.input, step.output,
+                    ));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! FNV-1a hashing, for generated names which are derived from the input
+//! rather than chosen at random.
+
+use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
+use std::hash::Hasher;
+
+pub(crate) struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+// Identifies one invocation of the macro, for `@unique` names which are the
+// same throughout the invocation but differ from one invocation to another.
+// The Debug representation of the call site span includes its position and
+// the macro expansion it came from. It is not a stable format, so the hash may
+// change from one build or compiler version to the next.
+pub(crate) fn invocation(input: &TokenStream) -> u64 {
+    let mut hasher = Fnv::default();
+    hasher.write(format!("{:?}", Span::call_site()).as_bytes());
+    hasher.write(input.to_string().as_bytes());
+    hasher.finish()
+}
+
+// Short hex digest of the tokens passed to `@hash(...)`.
+pub(crate) fn digest(tokens: TokenStream) -> String {
+    let mut canonical = String::new();
+    canonicalize(tokens, &mut canonical);
+    let mut hasher = Fnv::default();
+    hasher.write(canonical.as_bytes());
+    let hash = hasher.finish();
+    format!("{:08x}", (hash ^ (hash >> 32)) & 0xffff_ffff)
+}
+
+// Writes the tokens with one space between them, independent of how the
+// compiler would print them or whether punctuation is joint, and with invisible
+// groups left out, so that a `$ty` gives the same digest as the tokens it was
+// invoked with.
+fn canonicalize(tokens: TokenStream, canonical: &mut String) {
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("( ", ")"),
+                    Delimiter::Brace => ("{ ", "}"),
+                    Delimiter::Bracket => ("[ ", "]"),
+                    Delimiter::None => {
+                        canonicalize(group.stream(), canonical);
+                        continue;
+                    }
+                };
+                canonical.push_str(open);
+                canonicalize(group.stream(), canonical);
+                canonical.push_str(close);
+            }
+            TokenTree::Ident(ident) => canonical.push_str(&ident.to_string()),
+            TokenTree::Literal(literal) => canonical.push_str(&literal.to_string()),
+            TokenTree::Punct(punct) => canonical.push(punct.as_char()),
+        }
+        canonical.push(' ');
+    }
+}
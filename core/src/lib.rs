@@ -0,0 +1,904 @@
+//! The engine behind the [`paste`] crate, for use by other procedural macros
+//! which need the same `[<`...`>]` semantics and modifiers as `paste!`.
+//!
+//! [`paste`]: https://docs.rs/paste
+//!
+//! ```
+//! use proc_macro2::TokenStream;
+//! use std::str::FromStr;
+//!
+//! let input = TokenStream::from_str("fn [<get_ UserId:snake>]() {}").unwrap();
+//! let expanded = match paste_core::expand(input) {
+//!     Ok(expanded) => expanded,
+//!     Err(err) => err.to_compile_error(),
+//! };
+//! assert_eq!(expanded.to_string(), "fn get_user_id () { }");
+//! ```
+
+#![doc(html_root_url = "https://docs.rs/paste-core/1.0.14")]
+#![allow(
+    clippy::derive_partial_eq_without_eq,
+    clippy::doc_markdown,
+    clippy::match_same_arms,
+    clippy::missing_errors_doc,
+    clippy::missing_panics_doc,
+    clippy::module_name_repetitions,
+    clippy::must_use_candidate,
+    clippy::too_many_lines
+)]
+
+mod attr;
+mod binding;
+mod derive;
+mod error;
+mod explain;
+mod hash;
+mod lint;
+mod mangle;
+mod modifier;
+mod options;
+pub mod segment;
+
+pub use crate::error::{Error, Result};
+
+use crate::attr::expand_attr;
+use crate::explain::Explain;
+use crate::options::Options;
+use crate::segment::{LitStr, Segment};
+use proc_macro2::{
+    token_stream, Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree,
+};
+use std::char;
+use std::iter::{self, Peekable};
+use std::panic;
+use std::str::FromStr;
+
+/// Expands the input of `paste!`, pasting together the contents of every
+/// `[<`...`>]` in it.
+///
+/// Leading `#![paste(...)]` directives are applied and removed. The input is
+/// returned unchanged if there is nothing to paste.
+pub fn expand(input: TokenStream) -> Result<TokenStream> {
+    let mut options = Options {
+        input: input.clone(),
+        ..Options::default()
+    };
+    let input = options::parse_directives(input, &mut options)?;
+    binding::evaluate(&mut options)?;
+    let input = binding::substitute(input, &options.bindings);
+    let mut contains_paste = !options.bindings.is_empty();
+    let flatten_single_interpolation = true;
+    let mut expanded = expand_tokens(
+        input.clone(),
+        &mut contains_paste,
+        flatten_single_interpolation,
+        &options,
+    )?;
+    if let Some(lints) = &options.lints {
+        expanded.extend(lints.to_tokens());
+    }
+    if contains_paste {
+        Ok(expanded)
+    } else {
+        Ok(input)
+    }
+}
+
+/// Expands an item annotated with `#[paste::apply(args...)]`.
+///
+/// With the `strict` argument and nothing to paste, the item is returned
+/// unchanged followed by a `compile_error!`.
+pub fn expand_attribute(args: TokenStream, input: TokenStream) -> Result<TokenStream> {
+    let mut options = Options {
+        input: input.clone(),
+        ..Options::default()
+    };
+    let strict = parse_apply_args(args, &mut options)?;
+    let mut contains_paste = false;
+    let flatten_single_interpolation = true;
+    let mut expanded = expand_tokens(
+        input.clone(),
+        &mut contains_paste,
+        flatten_single_interpolation,
+        &options,
+    )?;
+    if let Some(lints) = &options.lints {
+        expanded.extend(lints.to_tokens());
+    }
+    if contains_paste {
+        Ok(expanded)
+    } else if strict {
+        let msg = "nothing to paste in item annotated with #[paste::apply(strict)]";
+        let mut tokens = input;
+        tokens.extend(Error::new(Span::call_site(), msg).to_compile_error());
+        Ok(tokens)
+    } else {
+        Ok(input)
+    }
+}
+
+/// Applies modifiers in order to a string, exactly as `paste!` would apply
+/// them to a segment. No tokens are involved, so this can be called from build
+/// scripts and code generators to agree with `paste!` on names. An error is
+/// the message `paste!` would report for the same modifiers.
+///
+/// ```
+/// let pasted = paste_core::apply("UserId", &["snake", "upper"]).unwrap();
+/// assert_eq!(pasted, "USER_ID");
+///
+/// let pasted = paste_core::apply("HttpRequestHandler", &["words(1..)"]).unwrap();
+/// assert_eq!(pasted, "RequestHandler");
+/// ```
+pub fn apply(value: &str, modifiers: &[&str]) -> std::result::Result<String, String> {
+    let mut value = value.to_owned();
+    for modifier in modifiers {
+        let (name, args) = match modifier.find('(') {
+            Some(paren) if modifier.ends_with(')') => (
+                &modifier[..paren],
+                Some(&modifier[paren + 1..modifier.len() - 1]),
+            ),
+            _ => (*modifier, None),
+        };
+        value = modifier::apply(&value, name, args)?;
+    }
+    Ok(value)
+}
+
+/// Expands `#[derive(PasteNames)]` into associated string constants for the
+/// `#[paste(const NAME = [<`...`>])]` attributes of the input.
+pub fn derive_names(input: TokenStream) -> Result<TokenStream> {
+    derive::derive_names(input)
+}
+
+/// Reports how each `[<`...`>]` in the input is pasted, segment by segment.
+///
+/// The report consists of one error per paste, to be emitted in place of the
+/// expansion with [`Error::to_compile_error`].
+pub fn explain(input: TokenStream) -> Error {
+    let mut options = Options {
+        input: input.clone(),
+        ..Options::default()
+    };
+    let input = match options::parse_directives(input, &mut options) {
+        Ok(input) => input,
+        Err(err) => return err,
+    };
+    options.explain = Some(Explain::default());
+    if let Err(err) = binding::evaluate(&mut options) {
+        return err;
+    }
+    let input = binding::substitute(input, &options.bindings);
+    let mut contains_paste = false;
+    let flatten_single_interpolation = true;
+    let expanded = expand_tokens(
+        input,
+        &mut contains_paste,
+        flatten_single_interpolation,
+        &options,
+    );
+    let mut errors = options.explain.as_ref().and_then(Explain::to_error);
+    if let Err(err) = expanded {
+        Error::accumulate(&mut errors, err);
+    }
+    match errors {
+        Some(errors) => errors,
+        None => Error::new(Span::call_site(), "nothing to paste"),
+    }
+}
+
+fn parse_apply_args(args: TokenStream, options: &mut Options) -> Result<bool> {
+    let mut strict = false;
+    let mut tokens = args.into_iter();
+    while let Some(token) = tokens.next() {
+        let supported = match &token {
+            TokenTree::Ident(ident) if *ident == "strict" => {
+                strict = true;
+                true
+            }
+            TokenTree::Ident(ident) => options::parse_option(ident, options),
+            _ => false,
+        };
+        if !supported {
+            return Err(Error::new(
+                token.span(),
+                "unsupported paste::apply argument",
+            ));
+        }
+        match tokens.next() {
+            None => {}
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == ',' => {}
+            Some(wrong) => return Err(Error::new(wrong.span(), "expected `,`")),
+        }
+    }
+    Ok(strict)
+}
+
+fn expand_tokens(
+    input: TokenStream,
+    contains_paste: &mut bool,
+    flatten_single_interpolation: bool,
+    options: &Options,
+) -> Result<TokenStream> {
+    let mut expanded = TokenStream::new();
+    let mut errors = None::<Error>;
+    let mut lookbehind = Lookbehind::Other;
+    let mut prev_none_group = None::<Group>;
+    let mut prev_keyword = None::<String>;
+    let mut tokens = input.into_iter().peekable();
+    loop {
+        let token = tokens.next();
+        let keyword = match &token {
+            Some(TokenTree::Ident(ident)) => Some(ident.to_string()),
+            _ => None,
+        };
+        if let Some(group) = prev_none_group.take() {
+            if match (&token, tokens.peek()) {
+                (Some(TokenTree::Punct(fst)), Some(TokenTree::Punct(snd))) => {
+                    fst.as_char() == ':' && snd.as_char() == ':' && fst.spacing() == Spacing::Joint
+                }
+                _ => false,
+            } {
+                expanded.extend(group.stream());
+                *contains_paste = true;
+            } else {
+                expanded.extend(iter::once(TokenTree::Group(group)));
+            }
+        }
+        match token {
+            Some(TokenTree::Group(group)) => {
+                let delimiter = group.delimiter();
+                let content = group.stream();
+                let span = group.span();
+                if delimiter == Delimiter::Bracket && is_paste_operation(&content) {
+                    match paste_bracket(content, span, false, options) {
+                        Ok(tokens) => {
+                            if let (Some(lints), Some(keyword)) = (&options.lints, &prev_keyword) {
+                                for tt in tokens.clone() {
+                                    if let TokenTree::Ident(ident) = tt {
+                                        let name = ident.to_string();
+                                        lint::check_item_name(lints, keyword, &name, span);
+                                    }
+                                }
+                            }
+                            expanded.extend(tokens);
+                        }
+                        Err(err) => {
+                            // Keep expanding the rest of the input so that all
+                            // errors are reported together.
+                            Error::accumulate(&mut errors, err);
+                        }
+                    }
+                    *contains_paste = true;
+                } else if flatten_single_interpolation
+                    && delimiter == Delimiter::None
+                    && is_single_interpolation_group(&content)
+                {
+                    expanded.extend(content);
+                    *contains_paste = true;
+                } else {
+                    let mut group_contains_paste = false;
+                    let is_attribute = delimiter == Delimiter::Bracket
+                        && (lookbehind == Lookbehind::Pound || lookbehind == Lookbehind::PoundBang);
+                    let nested = if is_attribute {
+                        expand_attr(content.clone(), span, &mut group_contains_paste, options)
+                    } else {
+                        expand_tokens(
+                            content.clone(),
+                            &mut group_contains_paste,
+                            flatten_single_interpolation,
+                            options,
+                        )
+                    };
+                    let nested = match nested {
+                        Ok(nested) => nested,
+                        Err(err) => {
+                            Error::accumulate(&mut errors, err);
+                            content
+                        }
+                    };
+                    let group = if group_contains_paste {
+                        let mut group = Group::new(delimiter, nested);
+                        group.set_span(span);
+                        *contains_paste = true;
+                        group
+                    } else {
+                        group.clone()
+                    };
+                    if delimiter != Delimiter::None {
+                        expanded.extend(iter::once(TokenTree::Group(group)));
+                    } else if lookbehind == Lookbehind::DoubleColon {
+                        expanded.extend(group.stream());
+                        *contains_paste = true;
+                    } else {
+                        prev_none_group = Some(group);
+                    }
+                }
+                lookbehind = Lookbehind::Other;
+            }
+            Some(TokenTree::Punct(punct)) => {
+                lookbehind = match punct.as_char() {
+                    ':' if lookbehind == Lookbehind::JointColon => Lookbehind::DoubleColon,
+                    ':' if punct.spacing() == Spacing::Joint => Lookbehind::JointColon,
+                    '#' => Lookbehind::Pound,
+                    '!' if lookbehind == Lookbehind::Pound => Lookbehind::PoundBang,
+                    _ => Lookbehind::Other,
+                };
+                expanded.extend(iter::once(TokenTree::Punct(punct)));
+            }
+            Some(other) => {
+                lookbehind = Lookbehind::Other;
+                expanded.extend(iter::once(other));
+            }
+            None => {
+                return match errors {
+                    Some(errors) => Err(errors),
+                    None => Ok(expanded),
+                };
+            }
+        }
+        prev_keyword = keyword;
+    }
+}
+
+#[derive(PartialEq)]
+enum Lookbehind {
+    JointColon,
+    DoubleColon,
+    Pound,
+    PoundBang,
+    Other,
+}
+
+// https://github.com/dtolnay/paste/issues/26
+fn is_single_interpolation_group(input: &TokenStream) -> bool {
+    #[derive(PartialEq)]
+    enum State {
+        Init,
+        Ident,
+        Literal,
+        Apostrophe,
+        Lifetime,
+        Colon1,
+        Colon2,
+    }
+
+    let mut state = State::Init;
+    for tt in input.clone() {
+        state = match (state, &tt) {
+            (State::Init, TokenTree::Ident(_)) => State::Ident,
+            (State::Init, TokenTree::Literal(_)) => State::Literal,
+            (State::Init, TokenTree::Punct(punct)) if punct.as_char() == '\'' => State::Apostrophe,
+            (State::Apostrophe, TokenTree::Ident(_)) => State::Lifetime,
+            (State::Ident, TokenTree::Punct(punct))
+                if punct.as_char() == ':' && punct.spacing() == Spacing::Joint =>
+            {
+                State::Colon1
+            }
+            (State::Colon1, TokenTree::Punct(punct))
+                if punct.as_char() == ':' && punct.spacing() == Spacing::Alone =>
+            {
+                State::Colon2
+            }
+            (State::Colon2, TokenTree::Ident(_)) => State::Ident,
+            _ => return false,
+        };
+    }
+
+    state == State::Ident || state == State::Literal || state == State::Lifetime
+}
+
+fn is_paste_operation(input: &TokenStream) -> bool {
+    let mut tokens = input.clone().into_iter();
+
+    match &tokens.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {}
+        _ => return false,
+    }
+
+    let mut has_token = false;
+    loop {
+        match &tokens.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '>' => {
+                return has_token && tokens.next().is_none();
+            }
+            Some(_) => has_token = true,
+            None => return false,
+        }
+    }
+}
+
+// Expands the contents of `[< ... >]`. With `as_string`, as inside the text of
+// a doc comment or in the value of a cfg predicate, the paste produces a string
+// literal in place of an identifier.
+fn paste_bracket(
+    content: TokenStream,
+    span: Span,
+    as_string: bool,
+    options: &Options,
+) -> Result<TokenStream> {
+    let (mut output, mut segments) = parse_bracket_as_segments(content, span)?;
+    options.apply_acronyms(&mut segments);
+    if let Output::Join(_, lines) = &mut output {
+        for line in lines {
+            options.apply_acronyms(line);
+        }
+    }
+    if let Some(lints) = &options.lints {
+        lint::check_modifiers(lints, &segments);
+        if let Output::Join(_, lines) = &output {
+            for line in lines {
+                lint::check_modifiers(lints, line);
+            }
+        }
+    }
+    let pasted = match &output {
+        Output::Ident | Output::Spanned(..) | Output::Unique if as_string => {
+            let mut pasted = segment::paste(&segments)?;
+            if let Output::Unique = output {
+                pasted = unique_name(&pasted, options);
+            }
+            let mut literal = Literal::string(&pasted);
+            literal.set_span(span);
+            Ok(TokenStream::from(TokenTree::Literal(literal)))
+        }
+        Output::Ident => {
+            let pasted = segment::paste(&segments)?;
+            check_decimal_point(&segments, &pasted)?;
+            pasted_to_tokens(pasted, span, &segments)
+        }
+        Output::Spanned(_, ident_span) => {
+            let pasted = segment::paste(&segments)?;
+            check_decimal_point(&segments, &pasted)?;
+            let tokens = pasted_to_tokens(pasted, span, &segments)?;
+            Ok(tokens
+                .into_iter()
+                .map(|mut tt| {
+                    tt.set_span(*ident_span);
+                    tt
+                })
+                .collect())
+        }
+        Output::Unique => {
+            let pasted = segment::paste(&segments)?;
+            pasted_to_tokens(unique_name(&pasted, options), span, &segments)
+        }
+        Output::Char => {
+            let pasted = segment::paste(&segments)?;
+            pasted_to_char(&pasted, span)
+        }
+        Output::Fmt(template) => {
+            let mut is_lifetime = false;
+            let args = segment::evaluate(&segments, &mut is_lifetime)?;
+            format_to_string(template, &args, span)
+        }
+        Output::Join(separator, lines) => {
+            let mut contents = String::new();
+            for (i, line) in lines.iter().enumerate() {
+                if i > 0 {
+                    contents.push_str(&separator.value);
+                }
+                contents.push_str(&segment::paste(line)?);
+            }
+            escaped_to_string(&contents, span)
+        }
+    }?;
+    if let Some(explain) = &options.explain {
+        let (mode, lines) = explain_output(&output, &segments);
+        explain.record(span, mode, &lines, &pasted);
+    }
+    Ok(pasted)
+}
+
+fn unique_name(base: &str, options: &Options) -> String {
+    format!("__{}_{:016x}", base, options.unique())
+}
+
+fn explain_output<'a>(
+    output: &'a Output,
+    segments: &'a [Segment],
+) -> (&'static str, Vec<&'a [Segment]>) {
+    match output {
+        Output::Ident => ("", vec![segments]),
+        Output::Spanned(mode, _) => (mode, vec![segments]),
+        Output::Unique => ("@unique", vec![segments]),
+        Output::Char => ("@char", vec![segments]),
+        Output::Fmt(_) => ("@fmt", vec![segments]),
+        Output::Join(_, lines) => ("@join", lines.iter().map(Vec::as_slice).collect()),
+    }
+}
+
+enum Output {
+    Ident,
+    // An identifier or literal with the span picked by `@call_site`,
+    // `@mixed_site` or `@span(...)`, instead of the span of the bracket.
+    Spanned(&'static str, Span),
+    // An identifier private to this invocation of the macro.
+    Unique,
+    Char,
+    Fmt(LitStr),
+    Join(LitStr, Vec<Vec<Segment>>),
+}
+
+fn parse_bracket_as_segments(input: TokenStream, scope: Span) -> Result<(Output, Vec<Segment>)> {
+    let mut tokens = input.into_iter().peekable();
+
+    match &tokens.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {}
+        Some(wrong) => return Err(Error::new(wrong.span(), "expected `<`")),
+        None => return Err(Error::new(scope, "expected `[< ... >]`")),
+    }
+
+    let mut output = Output::Ident;
+    if let Some(TokenTree::Punct(punct)) = tokens.peek() {
+        if punct.as_char() == '@' && !segment::starts_with_function(&tokens) {
+            let at = tokens.next().unwrap();
+            match tokens.next() {
+                Some(TokenTree::Ident(ident)) if ident == "char" => {
+                    output = Output::Char;
+                }
+                Some(TokenTree::Ident(ident)) if ident == "fmt" => {
+                    let template = parse_string_arg(&mut tokens, &ident, "format string")?;
+                    output = Output::Fmt(template);
+                }
+                Some(TokenTree::Ident(ident)) if ident == "unique" => {
+                    output = Output::Unique;
+                }
+                Some(TokenTree::Ident(ident)) if ident == "call_site" => {
+                    output = Output::Spanned("@call_site", Span::call_site());
+                }
+                Some(TokenTree::Ident(ident)) if ident == "mixed_site" => {
+                    output = Output::Spanned("@mixed_site", Span::mixed_site());
+                }
+                Some(TokenTree::Ident(ident)) if ident == "span" => {
+                    let span = parse_span_arg(&mut tokens, &ident)?;
+                    output = Output::Spanned("@span", span);
+                }
+                Some(TokenTree::Ident(ident)) if ident == "join" => {
+                    let separator = parse_string_arg(&mut tokens, &ident, "separator string")?;
+                    let lines = parse_join_lines(&mut tokens)?;
+                    output = Output::Join(separator, lines);
+                }
+                Some(wrong) => {
+                    return Err(Error::new2(at.span(), wrong.span(), "unsupported `@` mode"));
+                }
+                None => return Err(Error::new(at.span(), "expected identifier after `@`")),
+            }
+        }
+    }
+
+    let mut segments = segment::parse_raw(&mut tokens)?;
+
+    match &tokens.next() {
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '>' => {}
+        Some(wrong) => return Err(Error::new(wrong.span(), "expected `>`")),
+        None => return Err(Error::new(scope, "expected `[< ... >]`")),
+    }
+
+    if let Some(unexpected) = tokens.next() {
+        return Err(Error::new(
+            unexpected.span(),
+            "unexpected input, expected `[< ... >]`",
+        ));
+    }
+
+    if let Output::Fmt(_) = output {
+        for segment in &segments {
+            if let Segment::Apostrophe(span) = segment {
+                return Err(Error::new(*span, "unexpected lifetime"));
+            }
+        }
+        attr::unquote_segments(&mut segments)?;
+        return Ok((output, segments));
+    }
+
+    segment::unquote_literals(&mut segments)?;
+    Ok((output, segments))
+}
+
+// The span of the token in parentheses after `@span`.
+fn parse_span_arg(tokens: &mut Peekable<token_stream::IntoIter>, mode: &Ident) -> Result<Span> {
+    match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            match group.stream().into_iter().next() {
+                Some(token) => Ok(token.span()),
+                None => Err(Error::new(
+                    group.span(),
+                    "expected a token whose span to use",
+                )),
+            }
+        }
+        Some(wrong) => Err(Error::new(wrong.span(), "expected `(`")),
+        None => Err(Error::new(mode.span(), "expected `(` after `@span`")),
+    }
+}
+
+// The string literal following `@fmt` or `@join`, escaped as the contents of
+// a string literal.
+fn parse_string_arg(
+    tokens: &mut Peekable<token_stream::IntoIter>,
+    mode: &Ident,
+    expected: &str,
+) -> Result<LitStr> {
+    let msg = format!("expected {}", expected);
+    let lit = match tokens.next() {
+        Some(TokenTree::Literal(lit)) => lit,
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::None => {
+            let mut inner = group.stream().into_iter();
+            match (inner.next(), inner.next()) {
+                (Some(TokenTree::Literal(lit)), None) => lit,
+                _ => return Err(Error::new(group.span(), &msg)),
+            }
+        }
+        Some(wrong) => return Err(Error::new(wrong.span(), &msg)),
+        None => {
+            let msg = format!("{} after `@{}`", msg, mode);
+            return Err(Error::new(mode.span(), &msg));
+        }
+    };
+
+    let repr = lit.to_string();
+    let value = if repr.starts_with('"') {
+        repr[1..repr.len() - 1].to_owned()
+    } else if repr.starts_with('r') {
+        let begin = repr.find('"').unwrap() + 1;
+        let end = repr.rfind('"').unwrap();
+        let mut escaped = String::new();
+        for ch in repr[begin..end].chars() {
+            escaped.extend(ch.escape_debug());
+        }
+        escaped
+    } else {
+        return Err(Error::new(lit.span(), &msg));
+    };
+
+    Ok(LitStr {
+        value,
+        span: lit.span(),
+    })
+}
+
+// @join "sep" $(($($segment)*))*
+//
+// Each parenthesized group is pasted the same way as the value of a doc
+// attribute, and the results are joined by the separator.
+fn parse_join_lines(tokens: &mut Peekable<token_stream::IntoIter>) -> Result<Vec<Vec<Segment>>> {
+    let mut lines = Vec::new();
+    loop {
+        match tokens.peek() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {}
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::None => {
+                // A repetition passed through a macro_rules fragment.
+                let group = match tokens.next() {
+                    Some(TokenTree::Group(group)) => group,
+                    _ => unreachable!(),
+                };
+                let mut inner = group.stream().into_iter().peekable();
+                lines.extend(parse_join_lines(&mut inner)?);
+                if let Some(unexpected) = inner.next() {
+                    return Err(Error::new(unexpected.span(), "expected `(`"));
+                }
+                continue;
+            }
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ',' && !lines.is_empty() => {
+                tokens.next().unwrap();
+                continue;
+            }
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '>' => return Ok(lines),
+            Some(wrong) => return Err(Error::new(wrong.span(), "expected `(`")),
+            None => return Ok(lines),
+        }
+        let group = match tokens.next() {
+            Some(TokenTree::Group(group)) => group,
+            _ => unreachable!(),
+        };
+        let mut inner = group.stream().into_iter().peekable();
+        let mut line = segment::parse_raw(&mut inner)?;
+        if let Some(unexpected) = inner.next() {
+            return Err(Error::new(unexpected.span(), "unexpected token"));
+        }
+        for segment in &line {
+            if let Segment::Apostrophe(span) = segment {
+                return Err(Error::new(*span, "unexpected lifetime"));
+            }
+        }
+        attr::unquote_segments(&mut line)?;
+        lines.push(line);
+    }
+}
+
+// Substitutes each `{}` in the format string with the value of one segment.
+// Both the format string and the values are already escaped as the contents of
+// a string literal.
+fn format_to_string(template: &LitStr, args: &[String], span: Span) -> Result<TokenStream> {
+    let mut contents = String::new();
+    let mut placeholders = 0;
+    let mut chars = template.value.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match (ch, chars.peek()) {
+            ('\\', Some(&escaped)) => {
+                // The braces of a `\u{...}` escape are not placeholders.
+                contents.push(ch);
+                contents.push(escaped);
+                chars.next();
+                if escaped == 'u' {
+                    for ch in chars.by_ref() {
+                        contents.push(ch);
+                        if ch == '}' {
+                            break;
+                        }
+                    }
+                }
+            }
+            ('{', Some('{')) | ('}', Some('}')) => {
+                contents.push(ch);
+                chars.next();
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                if let Some(arg) = args.get(placeholders) {
+                    contents.push_str(arg);
+                }
+                placeholders += 1;
+            }
+            ('{' | '}', _) => {
+                return Err(Error::new(
+                    template.span,
+                    "invalid format string, expected `{}`, `{{` or `}}`",
+                ));
+            }
+            _ => contents.push(ch),
+        }
+    }
+
+    if placeholders != args.len() {
+        return Err(Error::new(
+            span,
+            &format!(
+                "format string has {} placeholder{} but {} segment{} given",
+                placeholders,
+                if placeholders == 1 { "" } else { "s" },
+                args.len(),
+                if args.len() == 1 { " was" } else { "s were" },
+            ),
+        ));
+    }
+
+    escaped_to_string(&contents, span)
+}
+
+// Builds a string literal from contents which are already escaped.
+fn escaped_to_string(contents: &str, span: Span) -> Result<TokenStream> {
+    let mut lit = match TokenStream::from_str(&format!("\"{}\"", contents)) {
+        Ok(tokens) => tokens.into_iter().next().unwrap(),
+        Err(_) => {
+            return Err(Error::new(
+                span,
+                "pasted string is not a valid string literal",
+            ))
+        }
+    };
+    lit.set_span(span);
+    Ok(TokenStream::from(lit))
+}
+
+// A decimal point is only meaningful if the pasted result is a float literal.
+fn check_decimal_point(segments: &[Segment], pasted: &str) -> Result<()> {
+    if pasted.starts_with(|ch: char| ch.is_ascii_digit()) {
+        return Ok(());
+    }
+    for segment in segments {
+        if let Segment::String(string) = segment {
+            if string.value.contains('.') {
+                return Err(Error::new(string.span, "unsupported literal"));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn pasted_to_tokens(mut pasted: String, span: Span, segments: &[Segment]) -> Result<TokenStream> {
+    let mut tokens = TokenStream::new();
+
+    if pasted.starts_with(|ch: char| ch.is_ascii_digit()) {
+        let literal = match panic::catch_unwind(|| Literal::from_str(&pasted)) {
+            Ok(Ok(literal)) => TokenTree::Literal(literal),
+            Ok(Err(_)) | Err(_) => {
+                return Err(Error::new(
+                    span,
+                    &format!("`{:?}` is not a valid literal", pasted),
+                ));
+            }
+        };
+        tokens.extend(iter::once(literal));
+        return Ok(tokens);
+    }
+
+    if pasted.starts_with('\'') {
+        let mut apostrophe = TokenTree::Punct(Punct::new('\'', Spacing::Joint));
+        apostrophe.set_span(span);
+        tokens.extend(iter::once(apostrophe));
+        pasted.remove(0);
+    }
+
+    let ident = match panic::catch_unwind(|| Ident::new(&pasted, span)) {
+        Ok(ident) => TokenTree::Ident(ident),
+        Err(_) => return Err(invalid_ident(&pasted, !tokens.is_empty(), span, segments)),
+    };
+
+    tokens.extend(iter::once(ident));
+    Ok(tokens)
+}
+
+// Points at the segment which introduced the first character that cannot be
+// part of an identifier, and shows what each segment evaluated to.
+fn invalid_ident(pasted: &str, is_lifetime: bool, span: Span, segments: &[Segment]) -> Error {
+    let pasted = if is_lifetime {
+        format!("'{}", pasted)
+    } else {
+        pasted.to_owned()
+    };
+    let msg = format!("`{:?}` is not a valid identifier", pasted);
+    let mut is_lifetime = false;
+    let evaluated = match segment::evaluate_spanned(segments, &mut is_lifetime) {
+        Ok(evaluated) => evaluated,
+        Err(_) => return Error::new(span, &msg),
+    };
+    let offending = evaluated.iter().find_map(|(segment_span, value)| {
+        let ch = value.chars().find(|&ch| !is_ident_char(ch))?;
+        Some((*segment_span, ch))
+    });
+    let (segment_span, ch) = match offending {
+        Some(offending) => offending,
+        None => return Error::new(span, &msg),
+    };
+
+    let values: Vec<String> = evaluated
+        .iter()
+        .map(|(_segment_span, value)| format!("{:?}", value))
+        .collect();
+    let sanitized: String = pasted
+        .chars()
+        .enumerate()
+        .map(|(i, ch)| {
+            if is_ident_char(ch) || i == 0 && ch == '\'' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    // One message, so that a paste in expression position reports a single
+    // compile_error! with everything in it.
+    let msg = format!(
+        "{}\n\
+         note: the segments of this paste evaluated to {}\n\
+         help: {:?} cannot appear in an identifier, consider sanitizing the input to paste `{}` instead",
+        msg,
+        values.join(", "),
+        ch,
+        sanitized,
+    );
+    Error::new(segment_span, &msg)
+}
+
+fn is_ident_char(ch: char) -> bool {
+    ch == '_' || ch.is_alphanumeric()
+}
+
+fn pasted_to_char(pasted: &str, span: Span) -> Result<TokenStream> {
+    let mut chars = pasted.chars();
+    let ch = match (chars.next(), chars.next()) {
+        (Some(ch), None) => ch,
+        _ => {
+            return Err(Error::new(
+                span,
+                &format!("`{:?}` is not a single character", pasted),
+            ));
+        }
+    };
+
+    let mut literal = Literal::character(ch);
+    literal.set_span(span);
+    Ok(TokenStream::from(TokenTree::Literal(literal)))
+}
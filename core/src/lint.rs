@@ -0,0 +1,194 @@
+use crate::segment::{self, Segment};
+use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use std::cell::RefCell;
+use std::iter::FromIterator;
+
+// Warnings collected while expanding with the `lints` option, to be emitted
+// alongside the expanded tokens.
+#[derive(Default)]
+pub struct Lints {
+    warnings: RefCell<Vec<Warning>>,
+}
+
+struct Warning {
+    span: Span,
+    msg: String,
+}
+
+impl Lints {
+    fn warn(&self, span: Span, msg: String) {
+        self.warnings.borrow_mut().push(Warning { span, msg });
+    }
+
+    // A proc macro on stable has no way to emit a warning directly, so each one
+    // is reported through the deprecation lint instead:
+    //
+    //     const _: () = {
+    //         #[deprecated(note = $msg)]
+    //         struct PasteWarning;
+    //         #[allow(dead_code)]
+    //         fn warn() {
+    //             let _ = PasteWarning;
+    //         }
+    //     };
+    pub fn to_tokens(&self) -> TokenStream {
+        let mut tokens = TokenStream::new();
+        for warning in self.warnings.borrow().iter() {
+            let span = warning.span;
+            let call_site = Span::call_site();
+            let body = TokenStream::from_iter(vec![
+                punct('#'),
+                group(
+                    Delimiter::Bracket,
+                    vec![
+                        ident("deprecated", call_site),
+                        group(
+                            Delimiter::Parenthesis,
+                            vec![
+                                ident("note", call_site),
+                                punct('='),
+                                TokenTree::Literal(Literal::string(&warning.msg)),
+                            ],
+                        ),
+                    ],
+                ),
+                ident("struct", call_site),
+                ident("PasteWarning", call_site),
+                punct(';'),
+                punct('#'),
+                group(
+                    Delimiter::Bracket,
+                    vec![
+                        ident("allow", call_site),
+                        group(Delimiter::Parenthesis, vec![ident("dead_code", call_site)]),
+                    ],
+                ),
+                ident("fn", call_site),
+                ident("warn", call_site),
+                group(Delimiter::Parenthesis, Vec::new()),
+                group(
+                    Delimiter::Brace,
+                    vec![
+                        ident("let", call_site),
+                        ident("_", call_site),
+                        punct('='),
+                        ident("PasteWarning", span),
+                        punct(';'),
+                    ],
+                ),
+            ]);
+            tokens.extend(vec![
+                ident("const", call_site),
+                ident("_", call_site),
+                punct(':'),
+                group(Delimiter::Parenthesis, Vec::new()),
+                punct('='),
+                TokenTree::Group(Group::new(Delimiter::Brace, body)),
+                punct(';'),
+            ]);
+        }
+        tokens
+    }
+}
+
+fn ident(name: &str, span: Span) -> TokenTree {
+    TokenTree::Ident(Ident::new(name, span))
+}
+
+fn punct(ch: char) -> TokenTree {
+    TokenTree::Punct(Punct::new(ch, Spacing::Alone))
+}
+
+fn group(delimiter: Delimiter, tokens: Vec<TokenTree>) -> TokenTree {
+    TokenTree::Group(Group::new(delimiter, TokenStream::from_iter(tokens)))
+}
+
+// Case modifiers which had no effect, or which lost information that a later
+// conversion would not be able to recover.
+pub fn check_modifiers(lints: &Lints, segments: &[Segment]) {
+    let mut is_lifetime = false;
+    let mut steps = Vec::new();
+    if segment::evaluate_traced(segments, &mut is_lifetime, &mut steps).is_err() {
+        return;
+    }
+    for step in &steps {
+        let modifier = step.modifier.to_string();
+        let span = step.modifier.span();
+        let is_case = matches!(
+            modifier.as_str(),
+            "lower" | "upper" | "snake" | "camel" | "lowerCamel" | "kebab"
+        );
+        if is_case && step.input == step.output {
+            lints.warn(
+                span,
+                format!("modifier `:{}` has no effect on `{}`", modifier, step.input),
+            );
+        } else if modifier == "camel" && has_digit_separator(&step.input) {
+            lints.warn(
+                span,
+                format!(
+                    "`:camel` drops the `_` next to a digit in `{}`, which `:snake` cannot restore",
+                    step.input,
+                ),
+            );
+        } else if modifier == "snake" && step.args.is_none() && has_acronym(&step.input) {
+            lints.warn(
+                span,
+                format!(
+                    "`:snake` splits the consecutive capitals in `{}` into separate words, list acronyms in `:snake(...)` to keep them together",
+                    step.input,
+                ),
+            );
+        }
+    }
+}
+
+fn has_digit_separator(input: &str) -> bool {
+    let chars: Vec<char> = input.chars().collect();
+    chars.windows(2).any(|pair| {
+        pair[0] == '_' && pair[1].is_ascii_digit() || pair[0].is_ascii_digit() && pair[1] == '_'
+    })
+}
+
+fn has_acronym(input: &str) -> bool {
+    let chars: Vec<char> = input.chars().collect();
+    chars
+        .windows(2)
+        .any(|pair| pair[0].is_uppercase() && pair[1].is_uppercase())
+}
+
+// Names pasted right after an item keyword which do not follow the naming
+// convention for that kind of item.
+pub fn check_item_name(lints: &Lints, keyword: &str, name: &str, span: Span) {
+    let (kind, case, modifier) = match keyword {
+        "fn" => ("function", "snake_case", ":snake"),
+        "mod" => ("module", "snake_case", ":snake"),
+        "let" => ("variable", "snake_case", ":snake"),
+        "struct" => ("struct", "CamelCase", ":camel"),
+        "enum" => ("enum", "CamelCase", ":camel"),
+        "union" => ("union", "CamelCase", ":camel"),
+        "trait" => ("trait", "CamelCase", ":camel"),
+        "type" => ("type alias", "CamelCase", ":camel"),
+        "const" => ("constant", "SCREAMING_CASE", ":snake:upper"),
+        "static" => ("static", "SCREAMING_CASE", ":snake:upper"),
+        _ => return,
+    };
+    let name = name.trim_start_matches("r#").trim_matches('_');
+    if name.is_empty() {
+        return;
+    }
+    let matches = match case {
+        "snake_case" => !name.chars().any(char::is_uppercase),
+        "CamelCase" => name.starts_with(char::is_uppercase) && !name.contains('_'),
+        _ => !name.chars().any(char::is_lowercase),
+    };
+    if !matches {
+        lints.warn(
+            span,
+            format!(
+                "{} name `{}` is not {}, consider pasting it with `{}`",
+                kind, name, case, modifier,
+            ),
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! The identifier pasted by `@mangle(...)`, which spells out a type in
+//! CamelCase:
+//!
+//! ```text
+//! Vec<Option<u8>>        VecOptionU8
+//! std::vec::Vec<u8>      StdVecVecU8
+//! (u8, [u16; 4])         TupleU8Array4U16
+//! &'a mut [u8]           RefMutSliceU8
+//! *const ()              PtrConstUnit
+//! fn(u8) -> bool         FnU8ToBool
+//! ```
+//!
+//! Lifetimes and the punctuation between path segments and generic arguments
+//! are left out.
+
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use std::iter::Peekable;
+
+pub(crate) fn mangle(tokens: TokenStream) -> String {
+    let mut mangled = String::new();
+    mangle_tokens(tokens, &mut mangled);
+    mangled
+}
+
+fn mangle_tokens(tokens: TokenStream, mangled: &mut String) {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) => {
+                let word = ident.to_string();
+                push_word(mangled, word.strip_prefix("r#").unwrap_or(&word));
+            }
+            TokenTree::Literal(literal) => {
+                let repr = literal.to_string();
+                mangled.extend(repr.chars().filter(|ch| ch.is_alphanumeric()));
+            }
+            TokenTree::Punct(punct) => match punct.as_char() {
+                '&' => {
+                    mangled.push_str("Ref");
+                    if next_is_ident(&mut tokens, "mut") {
+                        mangled.push_str("Mut");
+                    }
+                }
+                '*' => {
+                    mangled.push_str("Ptr");
+                    if next_is_ident(&mut tokens, "const") {
+                        mangled.push_str("Const");
+                    } else if next_is_ident(&mut tokens, "mut") {
+                        mangled.push_str("Mut");
+                    }
+                }
+                '\'' => {
+                    // Lifetime name.
+                    tokens.next();
+                }
+                '-' if punct.spacing() == Spacing::Joint => {
+                    // `->`
+                    tokens.next();
+                    mangled.push_str("To");
+                }
+                '!' => mangled.push_str("Never"),
+                _ => {}
+            },
+            TokenTree::Group(group) => match group.delimiter() {
+                Delimiter::Parenthesis => {
+                    let elements = split(group.stream(), ',');
+                    match elements.len() {
+                        0 => mangled.push_str("Unit"),
+                        1 if !ends_with(&group.stream(), ',') => {
+                            mangle_tokens(group.stream(), mangled);
+                        }
+                        _ => {
+                            mangled.push_str("Tuple");
+                            for element in elements {
+                                mangle_tokens(element, mangled);
+                            }
+                        }
+                    }
+                }
+                Delimiter::Bracket => {
+                    let mut parts = split(group.stream(), ';').into_iter();
+                    let element = parts.next().unwrap_or_default();
+                    match parts.next() {
+                        Some(len) => {
+                            mangled.push_str("Array");
+                            mangle_tokens(len, mangled);
+                        }
+                        None => mangled.push_str("Slice"),
+                    }
+                    mangle_tokens(element, mangled);
+                }
+                Delimiter::Brace | Delimiter::None => mangle_tokens(group.stream(), mangled),
+            },
+        }
+    }
+}
+
+fn push_word(mangled: &mut String, word: &str) {
+    let mut chars = word.chars();
+    if let Some(first) = chars.next() {
+        mangled.extend(first.to_uppercase());
+        mangled.push_str(chars.as_str());
+    }
+}
+
+fn next_is_ident<I>(tokens: &mut Peekable<I>, keyword: &str) -> bool
+where
+    I: Iterator<Item = TokenTree>,
+{
+    let is_keyword = match tokens.peek() {
+        Some(TokenTree::Ident(ident)) => ident == keyword,
+        _ => false,
+    };
+    if is_keyword {
+        tokens.next();
+    }
+    is_keyword
+}
+
+// Splits at each `sep` which is not inside of generic arguments, leaving out an
+// empty part after a trailing separator.
+fn split(tokens: TokenStream, sep: char) -> Vec<TokenStream> {
+    let mut parts = Vec::new();
+    let mut part = TokenStream::new();
+    let mut depth = 0usize;
+    let mut prev_joint_dash = false;
+    for token in tokens {
+        if let TokenTree::Punct(punct) = &token {
+            let ch = punct.as_char();
+            if ch == sep && depth == 0 {
+                parts.push(part);
+                part = TokenStream::new();
+                prev_joint_dash = false;
+                continue;
+            } else if ch == '<' {
+                depth += 1;
+            } else if ch == '>' && !prev_joint_dash {
+                depth = depth.saturating_sub(1);
+            }
+            prev_joint_dash = ch == '-' && punct.spacing() == Spacing::Joint;
+        } else {
+            prev_joint_dash = false;
+        }
+        part.extend(Some(token));
+    }
+    if !part.is_empty() {
+        parts.push(part);
+    }
+    parts
+}
+
+fn ends_with(tokens: &TokenStream, ch: char) -> bool {
+    match tokens.clone().into_iter().last() {
+        Some(TokenTree::Punct(punct)) => punct.as_char() == ch,
+        _ => false,
+    }
+}
@@ -0,0 +1,365 @@
+//! The modifiers which may follow a segment, applied to its value as a plain
+//! string.
+
+use std::fmt::Write;
+use std::ops::Range;
+
+// Applies one modifier, with the arguments in parentheses after it if any, to
+// the value of the segment before it. The error is the message to report at the
+// modifier.
+pub(crate) fn apply(value: &str, modifier: &str, args: Option<&str>) -> Result<String, String> {
+    if let Some(args) = args {
+        let args: String = args.split_whitespace().collect();
+        return match modifier {
+            "words" => select_words(value, &format!("words({})", args), &args),
+            "camel" => with_acronyms(value, modifier, &parse_acronyms(":camel", &args)?),
+            "snake" => with_acronyms(value, modifier, &parse_acronyms(":snake", &args)?),
+            "ascii" => match args.as_str() {
+                "drop" | "replace" | "escape" => Ok(to_ascii(value, &args)),
+                _ => Err(format!(
+                    "expected `drop`, `replace` or `escape`, like `:ascii(drop)`, found `{}`",
+                    args,
+                )),
+            },
+            _ => Err(format!("modifier `:{}` does not take arguments", modifier)),
+        };
+    }
+    match modifier {
+        "lower" => Ok(value.to_lowercase()),
+        "upper" => Ok(value.to_uppercase()),
+        "first" | "last" => {
+            let ch = if modifier == "first" {
+                value.chars().next()
+            } else {
+                value.chars().next_back()
+            };
+            match ch {
+                Some(ch) => Ok(ch.to_string()),
+                None => Err(format!("expected nonempty segment before `:{}`", modifier)),
+            }
+        }
+        "ascii" => Ok(to_ascii(value, "replace")),
+        "first_word" => select_words(value, modifier, "0"),
+        "last_word" => select_words(value, modifier, "-1"),
+        "words" => {
+            Err("expected word index or range in parentheses, like `:words(1..)`".to_owned())
+        }
+        "unsuffixed" => {
+            if !is_numeric(value) {
+                return Err("expected numeric literal before `:unsuffixed`".to_owned());
+            }
+            Ok(unsuffixed(value).to_owned())
+        }
+        suffix if NUMERIC_SUFFIXES.contains(&suffix) => {
+            if !is_numeric(value) {
+                return Err(format!("expected numeric literal before `:{}`", suffix));
+            }
+            Ok(unsuffixed(value).to_owned() + suffix)
+        }
+        #[cfg(feature = "convert_case")]
+        "snake" => {
+            use convert_case::{Case, Casing};
+            Ok(value.to_case(Case::Snake))
+        }
+        #[cfg(not(feature = "convert_case"))]
+        "snake" => {
+            let mut acc = String::new();
+            let mut prev = '_';
+            for ch in value.chars() {
+                if ch.is_uppercase() && prev != '_' {
+                    acc.push('_');
+                }
+                acc.push(ch);
+                prev = ch;
+            }
+            Ok(acc.to_lowercase())
+        }
+        #[cfg(feature = "convert_case")]
+        "camel" => {
+            use convert_case::{Case, Casing};
+            Ok(value.to_case(Case::Pascal))
+        }
+        #[cfg(not(feature = "convert_case"))]
+        "camel" => {
+            let mut acc = String::new();
+            let mut prev = '_';
+            for ch in value.chars() {
+                if ch != '_' {
+                    if prev == '_' {
+                        for chu in ch.to_uppercase() {
+                            acc.push(chu);
+                        }
+                    } else if prev.is_uppercase() {
+                        for chl in ch.to_lowercase() {
+                            acc.push(chl);
+                        }
+                    } else {
+                        acc.push(ch);
+                    }
+                }
+                prev = ch;
+            }
+            Ok(acc)
+        }
+        #[cfg(feature = "convert_case")]
+        "lowerCamel" => {
+            use convert_case::{Case, Casing};
+            Ok(value.to_case(Case::Camel))
+        }
+        #[cfg(feature = "convert_case")]
+        "kebab" => {
+            use convert_case::{Case, Casing};
+            Ok(value.to_case(Case::Kebab))
+        }
+        _ => Err("unsupported modifier".to_owned()),
+    }
+}
+
+// The words of a segment, split at underscores and before each uppercase letter
+// which does not follow an underscore. This is how the builtin `:snake` splits,
+// but the split stays the same with the `convert_case` feature.
+fn words(value: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut prev = '_';
+    for (i, ch) in value.char_indices() {
+        if ch == '_' || ch.is_uppercase() && prev != '_' {
+            if let Some(start) = start.take() {
+                words.push(start..i);
+            }
+        }
+        if ch != '_' && start.is_none() {
+            start = Some(i);
+        }
+        prev = ch;
+    }
+    if let Some(start) = start {
+        words.push(start..value.len());
+    }
+    words
+}
+
+// The comma-separated acronyms in the arguments of `:camel` or `:snake`, or of
+// the `acronyms` directive.
+pub(crate) fn parse_acronyms(syntax: &str, args: &str) -> Result<Vec<String>, String> {
+    let acronyms: Vec<String> = args
+        .split(',')
+        .filter(|acronym| !acronym.is_empty())
+        .map(str::to_uppercase)
+        .collect();
+    let is_acronym = |acronym: &String| {
+        acronym.starts_with(char::is_alphabetic) && acronym.chars().all(char::is_alphanumeric)
+    };
+    if acronyms.is_empty() || !acronyms.iter().all(is_acronym) {
+        return Err(format!(
+            "expected acronyms, like `{}(HTTP, URL)`, found `{}`",
+            syntax, args,
+        ));
+    }
+    Ok(acronyms)
+}
+
+// `:camel` or `:snake` with a list of acronyms. Each acronym written in
+// capitals in the input is first spelled like an ordinary word, as in
+// `HTTPServer` to `HttpServer`, so that the plain conversion keeps it together
+// as one word, and `:camel` then spells it back in capitals. Everything else is
+// converted exactly as without the list.
+fn with_acronyms(value: &str, modifier: &str, acronyms: &[String]) -> Result<String, String> {
+    let mut input = String::new();
+    let mut in_capitals = false;
+    let mut i = 0;
+    while let Some(ch) = value[i..].chars().next() {
+        let rest = &value[i..];
+        let acronym = if in_capitals {
+            None
+        } else {
+            longest_word(rest, acronyms.iter().map(String::as_str))
+        };
+        if let Some(acronym) = acronym {
+            input.push_str(&capitalize(acronym));
+            in_capitals = false;
+            i += acronym.len();
+        } else {
+            input.push(ch);
+            in_capitals = ch.is_uppercase();
+            i += ch.len_utf8();
+        }
+    }
+
+    let converted = apply(&input, modifier, None)?;
+    if modifier != "camel" {
+        return Ok(converted);
+    }
+
+    let capitalized: Vec<String> = acronyms.iter().map(|acronym| capitalize(acronym)).collect();
+    let mut output = String::new();
+    let mut i = 0;
+    while let Some(ch) = converted[i..].chars().next() {
+        let rest = &converted[i..];
+        if let Some(word) = longest_word(rest, capitalized.iter().map(String::as_str)) {
+            output.push_str(&word.to_uppercase());
+            i += word.len();
+        } else {
+            output.push(ch);
+            i += ch.len_utf8();
+        }
+    }
+    Ok(output)
+}
+
+// The longest of the words which `rest` starts with as a whole word, that is,
+// not followed by more lowercase letters.
+fn longest_word<'a>(rest: &str, words: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    words
+        .filter(|word| {
+            !word.is_empty()
+                && rest.starts_with(word)
+                && !rest[word.len()..].starts_with(char::is_lowercase)
+        })
+        .max_by_key(|word| word.len())
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.as_str().to_lowercase().chars())
+            .collect(),
+        None => String::new(),
+    }
+}
+
+// Selects a word by index, or a range of words along with whatever separates
+// them, with negative indices counting from the end.
+fn select_words(value: &str, modifier: &str, selector: &str) -> Result<String, String> {
+    let words = words(value);
+    let len = words.len();
+    let invalid = || {
+        format!(
+            "expected word index or range, like `:words(1..)`, found `{}`",
+            selector,
+        )
+    };
+    let (begin, end) = if let Some((begin, end)) = selector.split_once("..") {
+        let (end, inclusive) = match end.strip_prefix('=') {
+            Some(end) => (end, true),
+            None => (end, false),
+        };
+        let begin = if begin.is_empty() {
+            0
+        } else {
+            parse_index(begin, len).ok_or_else(invalid)?
+        };
+        let end = if end.is_empty() && !inclusive {
+            len
+        } else {
+            parse_index(end, len).ok_or_else(invalid)? + usize::from(inclusive)
+        };
+        (begin, end)
+    } else {
+        let index = parse_index(selector, len).ok_or_else(invalid)?;
+        (index, index + 1)
+    };
+    if begin >= end || end > len {
+        return Err(format!(
+            "`:{}` selects no words of `{}`, which has {} word{}",
+            modifier,
+            value,
+            len,
+            if len == 1 { "" } else { "s" },
+        ));
+    }
+    Ok(value[words[begin].start..words[end - 1].end].to_owned())
+}
+
+fn parse_index(repr: &str, len: usize) -> Option<usize> {
+    match repr.strip_prefix('-') {
+        Some(from_end) => len.checked_sub(from_end.parse().ok()?),
+        None => repr.parse().ok(),
+    }
+}
+
+// Folds Latin letters with diacritics to the letters without, and drops
+// combining marks. Any other non-ASCII character is dropped, replaced by `_`,
+// or escaped as `u` followed by its hex code point, according to the fallback.
+fn to_ascii(value: &str, fallback: &str) -> String {
+    let mut acc = String::new();
+    for ch in value.chars() {
+        if ch.is_ascii() {
+            acc.push(ch);
+        } else if let Some(folded) = fold_latin(ch) {
+            if ch.is_lowercase() {
+                acc.push_str(&folded.to_lowercase());
+            } else {
+                acc.push_str(folded);
+            }
+        } else if ('\u{300}'..='\u{36f}').contains(&ch) {
+            // Combining diacritical mark, as in decomposed `e\u{301}`.
+        } else {
+            match fallback {
+                "drop" => {}
+                "escape" => {
+                    let _ = write!(acc, "u{:04x}", u32::from(ch));
+                }
+                _ => acc.push('_'),
+            }
+        }
+    }
+    acc
+}
+
+// The Latin-1 Supplement and Latin Extended-A letters, in capitals.
+fn fold_latin(ch: char) -> Option<&'static str> {
+    let folded = match ch {
+        'À'..='Å' | 'à'..='å' | 'Ā'..='ą' => "A",
+        'Æ' | 'æ' => "AE",
+        'Ç' | 'ç' | 'Ć'..='č' => "C",
+        'Ð' | 'ð' | 'Ď'..='đ' => "D",
+        'È'..='Ë' | 'è'..='ë' | 'Ē'..='ě' => "E",
+        'Ĝ'..='ģ' => "G",
+        'Ĥ'..='ħ' => "H",
+        'Ì'..='Ï' | 'ì'..='ï' | 'Ĩ'..='ı' => "I",
+        'Ĳ' | 'ĳ' => "IJ",
+        'Ĵ' | 'ĵ' => "J",
+        'Ķ'..='ĸ' => "K",
+        'Ĺ'..='ł' => "L",
+        'Ñ' | 'ñ' | 'Ń'..='ŋ' => "N",
+        'Ò'..='Ö' | 'Ø' | 'ò'..='ö' | 'ø' | 'Ō'..='ő' => "O",
+        'Œ' | 'œ' => "OE",
+        'Ŕ'..='ř' => "R",
+        'ß' => "SS",
+        'Ś'..='š' | 'ſ' => "S",
+        'Ţ'..='ŧ' => "T",
+        'Þ' | 'þ' => "TH",
+        'Ù'..='Ü' | 'ù'..='ü' | 'Ũ'..='ų' => "U",
+        'Ŵ' | 'ŵ' => "W",
+        'Ý' | 'ý' | 'ÿ' | 'Ŷ'..='Ÿ' => "Y",
+        'Ź'..='ž' => "Z",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+const NUMERIC_SUFFIXES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32",
+    "f64",
+];
+
+fn is_numeric(repr: &str) -> bool {
+    repr.starts_with(|ch: char| ch.is_ascii_digit())
+}
+
+fn unsuffixed(repr: &str) -> &str {
+    // In `0x1f32` the `f32` is made of hex digits, not a suffix.
+    let is_hex = repr.starts_with("0x");
+    for suffix in NUMERIC_SUFFIXES {
+        if is_hex && suffix.starts_with('f') {
+            continue;
+        }
+        if let Some(unsuffixed) = repr.strip_suffix(suffix) {
+            return unsuffixed;
+        }
+    }
+    repr
+}
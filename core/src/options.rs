@@ -0,0 +1,193 @@
+use crate::error::{Error, Result};
+use crate::explain::Explain;
+use crate::hash;
+use crate::lint::Lints;
+use crate::modifier;
+use crate::segment::Segment;
+use proc_macro2::{token_stream, Delimiter, Group, Ident, TokenStream, TokenTree};
+use std::cell::Cell;
+use std::iter::{self, Peekable};
+
+#[derive(Default)]
+pub struct Options {
+    pub interpolate_docs: bool,
+    pub lints: Option<Lints>,
+    pub explain: Option<Explain>,
+    // The whole input of this invocation, hashed into the suffix of the names
+    // pasted by `@unique` only once one of them is needed.
+    pub input: TokenStream,
+    pub unique: Cell<Option<u64>>,
+    // Bindings from `#![paste(let name = ...)]`, in the order they are listed.
+    pub bindings: Vec<(Ident, TokenStream)>,
+    // Acronyms from `#![paste(acronyms(...))]`, for `:camel` and `:snake`
+    // modifiers which do not list their own.
+    pub acronyms: Option<Group>,
+}
+
+impl Options {
+    // Suffix of the names pasted by `@unique` in this invocation.
+    pub fn unique(&self) -> u64 {
+        if let Some(unique) = self.unique.get() {
+            return unique;
+        }
+        let unique = hash::invocation(&self.input);
+        self.unique.set(Some(unique));
+        unique
+    }
+
+    pub fn apply_acronyms(&self, segments: &mut [Segment]) {
+        let acronyms = match &self.acronyms {
+            Some(acronyms) => acronyms,
+            None => return,
+        };
+        for segment in segments {
+            if let Segment::Modifier(_colon, modifier, args @ None) = segment {
+                if modifier == "camel" || modifier == "snake" {
+                    *args = Some(acronyms.clone());
+                }
+            }
+        }
+    }
+}
+
+// Removes the leading `#![paste(...)]` attributes from the input of `paste!`,
+// applying the options listed in them to the rest of the invocation.
+pub fn parse_directives(input: TokenStream, options: &mut Options) -> Result<TokenStream> {
+    let tokens: Vec<TokenTree> = input.clone().into_iter().collect();
+    let mut output = TokenStream::new();
+    let mut has_directive = false;
+    let mut i = 0;
+    while let Some(attr) = inner_attribute(&tokens[i..]) {
+        match paste_directive(attr) {
+            Some(args) => {
+                parse_option_list(args, options)?;
+                has_directive = true;
+            }
+            None => output.extend(tokens[i..i + 3].iter().cloned()),
+        }
+        i += 3;
+    }
+    if !has_directive {
+        // Rebuilding the input would lose information about the None-delimited
+        // groups in it, so keep the original when there is nothing to remove.
+        return Ok(input);
+    }
+    output.extend(tokens[i..].iter().cloned());
+    Ok(output)
+}
+
+// Sets the option named by `name`. Returns false if there is no such option.
+pub fn parse_option(name: &Ident, options: &mut Options) -> bool {
+    match name.to_string().as_str() {
+        "interpolate_docs" => options.interpolate_docs = true,
+        "lints" => options.lints = Some(Lints::default()),
+        _ => return false,
+    }
+    true
+}
+
+fn parse_option_list(args: TokenStream, options: &mut Options) -> Result<()> {
+    let mut tokens = args.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        let supported = match &token {
+            TokenTree::Ident(ident) if ident == "acronyms" => {
+                match tokens.next() {
+                    Some(TokenTree::Group(group))
+                        if group.delimiter() == Delimiter::Parenthesis =>
+                    {
+                        let args: String = group.stream().to_string().split_whitespace().collect();
+                        if let Err(msg) = modifier::parse_acronyms("acronyms", &args) {
+                            return Err(Error::new(group.span(), &msg));
+                        }
+                        options.acronyms = Some(group);
+                    }
+                    Some(wrong) => return Err(Error::new(wrong.span(), "expected `(`")),
+                    None => return Err(Error::new(ident.span(), "expected `(` after `acronyms`")),
+                }
+                true
+            }
+            TokenTree::Ident(ident) if ident == "let" => {
+                parse_binding(&mut tokens, ident, options)?;
+                true
+            }
+            TokenTree::Ident(ident) => parse_option(ident, options),
+            _ => false,
+        };
+        if !supported {
+            return Err(Error::new(token.span(), "unsupported paste option"));
+        }
+        match tokens.next() {
+            None => {}
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == ',' => {}
+            Some(wrong) => return Err(Error::new(wrong.span(), "expected `,`")),
+        }
+    }
+    Ok(())
+}
+
+// let $name = $value
+fn parse_binding(
+    tokens: &mut Peekable<token_stream::IntoIter>,
+    keyword: &Ident,
+    options: &mut Options,
+) -> Result<()> {
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(name)) => name,
+        Some(wrong) => return Err(Error::new(wrong.span(), "expected binding name")),
+        None => {
+            return Err(Error::new(
+                keyword.span(),
+                "expected binding name after `let`",
+            ))
+        }
+    };
+    match tokens.next() {
+        Some(TokenTree::Punct(ref punct)) if punct.as_char() == '=' => {}
+        Some(wrong) => return Err(Error::new(wrong.span(), "expected `=`")),
+        None => return Err(Error::new(name.span(), "expected `=` after binding name")),
+    }
+    let mut value = TokenStream::new();
+    while let Some(token) = tokens.peek() {
+        if let TokenTree::Punct(punct) = token {
+            if punct.as_char() == ',' {
+                break;
+            }
+        }
+        value.extend(iter::once(tokens.next().unwrap()));
+    }
+    if value.is_empty() {
+        return Err(Error::new(name.span(), "expected value of binding"));
+    }
+    options.bindings.push((name, value));
+    Ok(())
+}
+
+// #![...]
+fn inner_attribute(tokens: &[TokenTree]) -> Option<TokenStream> {
+    match (tokens.first(), tokens.get(1), tokens.get(2)) {
+        (
+            Some(TokenTree::Punct(pound)),
+            Some(TokenTree::Punct(bang)),
+            Some(TokenTree::Group(group)),
+        ) if pound.as_char() == '#'
+            && bang.as_char() == '!'
+            && group.delimiter() == Delimiter::Bracket =>
+        {
+            Some(group.stream())
+        }
+        _ => None,
+    }
+}
+
+// paste(...)
+fn paste_directive(attr: TokenStream) -> Option<TokenStream> {
+    let mut tokens = attr.into_iter();
+    match (tokens.next(), tokens.next(), tokens.next()) {
+        (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(group)), None)
+            if ident == "paste" && group.delimiter() == Delimiter::Parenthesis =>
+        {
+            Some(group.stream())
+        }
+        _ => None,
+    }
+}
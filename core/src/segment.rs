@@ -0,0 +1,367 @@
+//! The segments between `[<` and `>]`, and the modifiers applied to them.
+
+use crate::error::{Error, Result};
+use crate::hash;
+use crate::mangle;
+use crate::modifier;
+use proc_macro2::{token_stream, Delimiter, Group, Ident, Span, TokenTree};
+use std::iter::Peekable;
+
+/// One piece of the contents of a `[<`...`>]`.
+#[non_exhaustive]
+pub enum Segment {
+    String(LitStr),
+    Apostrophe(Span),
+    Env(LitStr),
+    Modifier(Colon, Ident, Option<Group>),
+}
+
+/// The string value of a segment, along with where it came from.
+#[non_exhaustive]
+pub struct LitStr {
+    pub value: String,
+    pub span: Span,
+}
+
+/// The `:` introducing a modifier.
+#[non_exhaustive]
+pub struct Colon {
+    pub span: Span,
+}
+
+// Whether the tokens start with a segment such as `@hash(...)` or
+// `@mangle(...)`, rather than with an `@` mode of the whole paste.
+pub(crate) fn starts_with_function(tokens: &Peekable<token_stream::IntoIter>) -> bool {
+    let mut tokens = tokens.clone();
+    match (tokens.next(), tokens.next(), tokens.next()) {
+        (
+            Some(TokenTree::Punct(at)),
+            Some(TokenTree::Ident(function)),
+            Some(TokenTree::Group(group)),
+        ) => {
+            at.as_char() == '@'
+                && (function == "hash" || function == "mangle")
+                && group.delimiter() == Delimiter::Parenthesis
+        }
+        _ => false,
+    }
+}
+
+/// Parses the contents of a `[<`...`>]` up to the closing `>`, taking the value
+/// of each literal the same way as `paste!`.
+pub fn parse(tokens: &mut Peekable<token_stream::IntoIter>) -> Result<Vec<Segment>> {
+    let mut segments = parse_raw(tokens)?;
+    unquote_literals(&mut segments)?;
+    Ok(segments)
+}
+
+// Like `parse`, but with each literal left as written, for `@fmt`, `@join` and
+// doc attributes to unquote in their own way.
+pub(crate) fn parse_raw(tokens: &mut Peekable<token_stream::IntoIter>) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    while match tokens.peek() {
+        None => false,
+        Some(TokenTree::Punct(punct)) => punct.as_char() != '>',
+        Some(_) => true,
+    } {
+        match tokens.next().unwrap() {
+            TokenTree::Ident(ident) => {
+                let mut fragment = ident.to_string();
+                if fragment.starts_with("r#") {
+                    fragment = fragment.split_off(2);
+                }
+                if fragment == "env"
+                    && match tokens.peek() {
+                        Some(TokenTree::Punct(punct)) => punct.as_char() == '!',
+                        _ => false,
+                    }
+                {
+                    let bang = tokens.next().unwrap(); // `!`
+                    let expect_group = tokens.next();
+                    let parenthesized = match &expect_group {
+                        Some(TokenTree::Group(group))
+                            if group.delimiter() == Delimiter::Parenthesis =>
+                        {
+                            group
+                        }
+                        Some(wrong) => return Err(Error::new(wrong.span(), "expected `(`")),
+                        None => {
+                            return Err(Error::new2(
+                                ident.span(),
+                                bang.span(),
+                                "expected `(` after `env!`",
+                            ));
+                        }
+                    };
+                    let mut inner = parenthesized.stream().into_iter();
+                    let lit = match inner.next() {
+                        Some(TokenTree::Literal(lit)) => lit,
+                        Some(wrong) => {
+                            return Err(Error::new(wrong.span(), "expected string literal"))
+                        }
+                        None => {
+                            return Err(Error::new2(
+                                ident.span(),
+                                parenthesized.span(),
+                                "expected string literal as argument to env! macro",
+                            ))
+                        }
+                    };
+                    let lit_string = lit.to_string();
+                    if lit_string.starts_with('"')
+                        && lit_string.ends_with('"')
+                        && lit_string.len() >= 2
+                    {
+                        // TODO: maybe handle escape sequences in the string if
+                        // someone has a use case.
+                        segments.push(Segment::Env(LitStr {
+                            value: lit_string[1..lit_string.len() - 1].to_owned(),
+                            span: lit.span(),
+                        }));
+                    } else {
+                        return Err(Error::new(lit.span(), "expected string literal"));
+                    }
+                    if let Some(unexpected) = inner.next() {
+                        return Err(Error::new(
+                            unexpected.span(),
+                            "unexpected token in env! macro",
+                        ));
+                    }
+                } else {
+                    segments.push(Segment::String(LitStr {
+                        value: fragment,
+                        span: ident.span(),
+                    }));
+                }
+            }
+            TokenTree::Literal(lit) => {
+                segments.push(Segment::String(LitStr {
+                    value: lit.to_string(),
+                    span: lit.span(),
+                }));
+            }
+            TokenTree::Punct(punct) => match punct.as_char() {
+                '_' => segments.push(Segment::String(LitStr {
+                    value: "_".to_owned(),
+                    span: punct.span(),
+                })),
+                '\'' => segments.push(Segment::Apostrophe(punct.span())),
+                ':' => {
+                    let colon_span = punct.span();
+                    let colon = Colon { span: colon_span };
+                    let ident = match tokens.next() {
+                        Some(TokenTree::Ident(ident)) => ident,
+                        wrong => {
+                            let span = wrong.as_ref().map_or(colon_span, TokenTree::span);
+                            return Err(Error::new(span, "expected identifier after `:`"));
+                        }
+                    };
+                    let args = match tokens.peek() {
+                        Some(TokenTree::Group(group))
+                            if group.delimiter() == Delimiter::Parenthesis =>
+                        {
+                            Some(group.clone())
+                        }
+                        _ => None,
+                    };
+                    if args.is_some() {
+                        tokens.next();
+                    }
+                    segments.push(Segment::Modifier(colon, ident, args));
+                }
+                '@' => {
+                    let function = match tokens.next() {
+                        Some(TokenTree::Ident(ident)) => ident,
+                        wrong => {
+                            let span = wrong.as_ref().map_or(punct.span(), TokenTree::span);
+                            return Err(Error::new(span, "expected identifier after `@`"));
+                        }
+                    };
+                    let args = match tokens.next() {
+                        Some(TokenTree::Group(group))
+                            if group.delimiter() == Delimiter::Parenthesis =>
+                        {
+                            group
+                        }
+                        Some(wrong) => return Err(Error::new(wrong.span(), "expected `(`")),
+                        None => {
+                            return Err(Error::new2(
+                                punct.span(),
+                                function.span(),
+                                &format!("expected `(` after `@{}`", function),
+                            ));
+                        }
+                    };
+                    let value = match function.to_string().as_str() {
+                        "hash" => hash::digest(args.stream()),
+                        "mangle" => {
+                            let mangled = mangle::mangle(args.stream());
+                            if mangled.is_empty() {
+                                return Err(Error::new(args.span(), "expected a type to mangle"));
+                            }
+                            mangled
+                        }
+                        _ => {
+                            return Err(Error::new2(
+                                punct.span(),
+                                function.span(),
+                                "unsupported `@` segment",
+                            ));
+                        }
+                    };
+                    segments.push(Segment::String(LitStr {
+                        value,
+                        span: function.span(),
+                    }));
+                }
+                _ => return Err(Error::new(punct.span(), "unexpected punct")),
+            },
+            TokenTree::Group(group) => {
+                if group.delimiter() == Delimiter::None {
+                    let mut inner = group.stream().into_iter().peekable();
+                    let nested = parse_raw(&mut inner)?;
+                    if let Some(unexpected) = inner.next() {
+                        return Err(Error::new(unexpected.span(), "unexpected token"));
+                    }
+                    segments.extend(nested);
+                } else {
+                    return Err(Error::new(group.span(), "unexpected token"));
+                }
+            }
+        }
+    }
+    Ok(segments)
+}
+
+// The value pasted for each literal: the contents of a string or character
+// literal with `-` replaced by `_`, or the literal as written for numbers.
+pub(crate) fn unquote_literals(segments: &mut [Segment]) -> Result<()> {
+    for segment in segments {
+        if let Segment::String(string) = segment {
+            if string.value.starts_with("'\\u{") {
+                let hex = &string.value[4..string.value.len() - 2];
+                if let Ok(unsigned) = u32::from_str_radix(hex, 16) {
+                    if let Some(ch) = char::from_u32(unsigned) {
+                        string.value.clear();
+                        string.value.push(ch);
+                        continue;
+                    }
+                }
+            }
+            let is_numeric = string.value.starts_with(|ch: char| ch.is_ascii_digit());
+            if string.value.contains(&['#', '\\', '+'][..])
+                || string.value.contains('.') && !is_numeric
+                || string.value.starts_with("b'")
+                || string.value.starts_with("b\"")
+                || string.value.starts_with("br\"")
+            {
+                return Err(Error::new(string.span, "unsupported literal"));
+            }
+            let mut range = 0..string.value.len();
+            if string.value.starts_with("r\"") {
+                range.start += 2;
+                range.end -= 1;
+            } else if string.value.starts_with(&['"', '\''][..]) {
+                range.start += 1;
+                range.end -= 1;
+            }
+            string.value = string.value[range].replace('-', "_");
+        }
+    }
+    Ok(())
+}
+
+/// Evaluates the segments and concatenates them into the pasted string.
+pub fn paste(segments: &[Segment]) -> Result<String> {
+    let mut is_lifetime = false;
+    let evaluated = evaluate(segments, &mut is_lifetime)?;
+
+    let mut pasted = evaluated.into_iter().collect::<String>();
+    if is_lifetime {
+        pasted.insert(0, '\'');
+    }
+    Ok(pasted)
+}
+
+/// The value of each segment after applying its modifiers.
+pub fn evaluate(segments: &[Segment], is_lifetime: &mut bool) -> Result<Vec<String>> {
+    let evaluated = evaluate_spanned(segments, is_lifetime)?;
+    Ok(evaluated.into_iter().map(|(_span, value)| value).collect())
+}
+
+// Same as `evaluate`, along with the span of the segment each value came from.
+pub(crate) fn evaluate_spanned(
+    segments: &[Segment],
+    is_lifetime: &mut bool,
+) -> Result<Vec<(Span, String)>> {
+    let mut steps = Vec::new();
+    evaluate_traced(segments, is_lifetime, &mut steps)
+}
+
+// The value of the environment variable named by `env!("...")`.
+pub(crate) fn resolve_env(var: &LitStr) -> Result<String> {
+    std::env::var(&var.value)
+        .map_err(|_| Error::new(var.span, &format!("no such env var: {:?}", var.value)))
+}
+
+// The application of one modifier to the value of a segment.
+pub(crate) struct Step {
+    pub modifier: Ident,
+    pub args: Option<String>,
+    pub input: String,
+    pub output: String,
+}
+
+// Same as `evaluate_spanned`, recording each modifier applied along the way.
+pub(crate) fn evaluate_traced(
+    segments: &[Segment],
+    is_lifetime: &mut bool,
+    steps: &mut Vec<Step>,
+) -> Result<Vec<(Span, String)>> {
+    let mut evaluated = Vec::new();
+    let mut spans = Vec::new();
+
+    for segment in segments {
+        match segment {
+            Segment::String(segment) => {
+                evaluated.push(segment.value.clone());
+                spans.push(segment.span);
+            }
+            Segment::Apostrophe(span) => {
+                if *is_lifetime {
+                    return Err(Error::new(*span, "unexpected lifetime"));
+                }
+                *is_lifetime = true;
+            }
+            Segment::Env(var) => {
+                let resolved = resolve_env(var)?.replace('-', "_");
+                evaluated.push(resolved);
+                spans.push(var.span);
+            }
+            Segment::Modifier(colon, ident, args) => {
+                let last = match evaluated.pop() {
+                    Some(last) => last,
+                    None => {
+                        return Err(Error::new2(colon.span, ident.span(), "unexpected modifier"))
+                    }
+                };
+                let span = spans.pop().unwrap();
+                let end = args.as_ref().map_or(ident.span(), Group::span);
+                let args = args.as_ref().map(|group| group.stream().to_string());
+                match modifier::apply(&last, &ident.to_string(), args.as_deref()) {
+                    Ok(value) => evaluated.push(value),
+                    Err(msg) => return Err(Error::new2(colon.span, end, &msg)),
+                }
+                spans.push(span);
+                steps.push(Step {
+                    modifier: ident.clone(),
+                    args,
+                    output: evaluated[evaluated.len() - 1].clone(),
+                    input: last,
+                });
+            }
+        }
+    }
+
+    Ok(spans.into_iter().zip(evaluated).collect())
+}
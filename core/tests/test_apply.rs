@@ -0,0 +1,89 @@
+#[test]
+fn test_case() {
+    assert_eq!(paste_core::apply("UserId", &["snake"]).unwrap(), "user_id");
+    assert_eq!(paste_core::apply("user_id", &["camel"]).unwrap(), "UserId");
+    assert_eq!(
+        paste_core::apply("UserId", &["snake", "upper"]).unwrap(),
+        "USER_ID",
+    );
+}
+
+#[test]
+fn test_no_modifiers() {
+    assert_eq!(paste_core::apply("UserId", &[]).unwrap(), "UserId");
+}
+
+#[test]
+fn test_numeric() {
+    assert_eq!(paste_core::apply("1u8", &["unsuffixed"]).unwrap(), "1");
+    assert_eq!(paste_core::apply("1u8", &["u64"]).unwrap(), "1u64");
+    assert_eq!(
+        paste_core::apply("0x1f32", &["unsuffixed"]).unwrap(),
+        "0x1f32",
+    );
+    assert_eq!(paste_core::apply("0x1f32", &["u16"]).unwrap(), "0x1f32u16");
+}
+
+#[test]
+fn test_words() {
+    assert_eq!(
+        paste_core::apply("HttpRequestHandler", &["first_word"]).unwrap(),
+        "Http",
+    );
+    assert_eq!(
+        paste_core::apply("HttpRequestHandler", &["words(-2)"]).unwrap(),
+        "Request",
+    );
+    assert_eq!(
+        paste_core::apply("__a__b__c__", &["words(1..)"]).unwrap(),
+        "b__c",
+    );
+    assert_eq!(
+        paste_core::apply("HTTPServer", &["words(..-1)"]).unwrap(),
+        "HTTP",
+    );
+    assert_eq!(
+        paste_core::apply("HTTPServer", &["first_word"]).unwrap(),
+        "H",
+    );
+}
+
+#[test]
+fn test_acronyms() {
+    assert_eq!(
+        paste_core::apply("http_url_id", &["camel(HTTP, URL, ID)"]).unwrap(),
+        "HTTPURLID",
+    );
+    assert_eq!(
+        paste_core::apply("HTTPServer", &["snake(HTTP)"]).unwrap(),
+        "http_server",
+    );
+}
+
+#[test]
+fn test_ascii() {
+    assert_eq!(paste_core::apply("Größe", &["ascii"]).unwrap(), "Grosse");
+    assert_eq!(
+        paste_core::apply("crème_brûlée", &["ascii", "camel"]).unwrap(),
+        "CremeBrulee",
+    );
+    assert_eq!(
+        paste_core::apply("e\u{301}", &["ascii(drop)"]).unwrap(),
+        "e",
+    );
+}
+
+#[test]
+fn test_error() {
+    let err = paste_core::apply("", &["first"]).unwrap_err();
+    assert_eq!(err, "expected nonempty segment before `:first`");
+
+    let err = paste_core::apply("x", &["nope"]).unwrap_err();
+    assert_eq!(err, "unsupported modifier");
+
+    let err = paste_core::apply("AbcDef", &["words(2)"]).unwrap_err();
+    assert_eq!(
+        err,
+        "`:words(2)` selects no words of `AbcDef`, which has 2 words",
+    );
+}
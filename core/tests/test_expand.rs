@@ -0,0 +1,73 @@
+use proc_macro2::TokenStream;
+use std::str::FromStr;
+
+fn expand(input: &str) -> String {
+    let input = TokenStream::from_str(input).unwrap();
+    match paste_core::expand(input) {
+        Ok(expanded) => expanded.to_string(),
+        Err(err) => panic!("{}", err.to_compile_error()),
+    }
+}
+
+#[test]
+fn test_ident() {
+    assert_eq!(expand("let [<a b c>] = 1;"), "let abc = 1 ;");
+}
+
+#[test]
+fn test_modifiers() {
+    assert_eq!(
+        expand("fn [<get_ UserId:snake>]() {}"),
+        "fn get_user_id () { }",
+    );
+    assert_eq!(expand("struct [<foo_bar:camel>];"), "struct FooBar ;");
+}
+
+#[test]
+fn test_nothing_to_paste() {
+    assert_eq!(expand("let x = [1, 2];"), "let x = [1 , 2] ;");
+}
+
+#[test]
+fn test_attribute() {
+    let args = TokenStream::new();
+    let input = TokenStream::from_str("fn [<a b>]() {}").unwrap();
+    match paste_core::expand_attribute(args, input) {
+        Ok(expanded) => assert_eq!(expanded.to_string(), "fn ab () { }"),
+        Err(err) => panic!("{}", err.to_compile_error()),
+    }
+}
+
+#[test]
+fn test_error() {
+    let input = TokenStream::from_str("let [<a - b>] = 1;").unwrap();
+    let err = paste_core::expand(input).unwrap_err();
+    assert!(err.to_compile_error().to_string().contains("compile_error"));
+}
+
+#[test]
+fn test_env_in_doc() {
+    std::env::set_var("PASTE_CORE_TEST_DOC_DIR", r"C:\Users\x");
+    std::env::set_var("PASTE_CORE_TEST_DOC_VERSION", "1.0.0-beta");
+    assert_eq!(
+        expand(r#"#[doc = "dir " env!("PASTE_CORE_TEST_DOC_DIR")] struct S;"#),
+        r#"# [doc = "dir C:\\Users\\x"] struct S ;"#,
+    );
+    assert_eq!(
+        expand(r#"#[doc = "v" env!("PASTE_CORE_TEST_DOC_VERSION")] struct S;"#),
+        r#"# [doc = "v1.0.0-beta"] struct S ;"#,
+    );
+}
+
+#[test]
+fn test_env_in_fmt() {
+    std::env::set_var("PASTE_CORE_TEST_FMT_DIR", r"C:\Users\x");
+    assert_eq!(
+        expand(r#"[<@fmt "dir {}" env!("PASTE_CORE_TEST_FMT_DIR")>]"#),
+        r#""dir C:\\Users\\x""#,
+    );
+    assert_eq!(
+        expand(r#"[<@join ", " ("dir " env!("PASTE_CORE_TEST_FMT_DIR")) (x)>]"#),
+        r#""dir C:\\Users\\x, x""#,
+    );
+}
@@ -0,0 +1,46 @@
+use paste_core::segment;
+use proc_macro2::TokenStream;
+use std::str::FromStr;
+
+fn paste(contents: &str) -> String {
+    let mut tokens = TokenStream::from_str(contents)
+        .unwrap()
+        .into_iter()
+        .peekable();
+    let segments = segment::parse(&mut tokens).unwrap();
+    assert!(tokens.next().is_none());
+    segment::paste(&segments).unwrap()
+}
+
+fn expand(contents: &str) -> String {
+    let input = TokenStream::from_str(&format!("[<{}>]", contents)).unwrap();
+    match paste_core::expand(input) {
+        Ok(expanded) => expanded.to_string(),
+        Err(err) => panic!("{}", err.to_compile_error()),
+    }
+}
+
+#[test]
+fn test_same_as_expand() {
+    for contents in [
+        "get_ UserId:snake",
+        r#"get_ "foo-bar" '\u{e9}' Baz:snake"#,
+        r#"r"raw" 'c' 1u8"#,
+        "r#loop _ x:upper",
+        "@hash(a b) _x",
+    ] {
+        assert_eq!(paste(contents), expand(contents), "{}", contents);
+    }
+}
+
+#[test]
+fn test_unsupported_literal() {
+    for contents in [r#""a\nb""#, "b'x'", r"'\n'"] {
+        let mut tokens = TokenStream::from_str(contents)
+            .unwrap()
+            .into_iter()
+            .peekable();
+        let err = segment::parse(&mut tokens).err().unwrap();
+        assert_eq!(err.to_string(), "unsupported literal");
+    }
+}
@@ -15,7 +15,10 @@
 //! This crate provides a flexible way to paste together identifiers in a macro,
 //! including using pasted identifiers to define new items.
 //!
-//! This approach works with any Rust compiler 1.31+.
+//! This approach works with any Rust compiler 1.56+. Earlier releases supported
+//! Rust 1.31+; the minimum went up when the expansion moved into the `paste-core`
+//! library crate, which is built on `proc-macro2` so that it can run outside of a
+//! procedural macro.
 //!
 //! <br>
 //!
@@ -107,6 +110,27 @@
 //! Use `$var:camel` to convert snake\_case to CamelCase.
 //! These compose, so for example `$var:snake:upper` would give you SCREAMING\_CASE.
 //!
+//! List acronyms in parentheses after `:camel` or `:snake` to keep them in
+//! capitals, so `[<http_url_id:camel(URL, ID)>]` pastes to `HttpURLID` and
+//! `[<HTTPServer:snake(HTTP)>]` to `http_server`. Start the input of `paste!` with
+//! `#![paste(acronyms(HTTP, URL, ID))]` to apply the same list to every `:camel`
+//! and `:snake` which does not list its own. Other words are converted the same as
+//! without a list.
+//!
+//! Use `$var:first_word` or `$var:last_word` to pick one word out of a segment,
+//! split into words at underscores and before every capital letter which does
+//! not follow an underscore, so `HTTPServer` is five words. Unlike `:snake`, the
+//! split does not depend on the `convert_case` feature. Use `$var:words(1..)`
+//! to pick a word or range of words by index, with negative indices counting
+//! from the end. For example `[<$ty:last_word>]` would paste to `Handler` and
+//! `[<$ty:words(..-1)>]` to `HttpRequest` if invoked with
+//! $ty=`HttpRequestHandler`.
+//!
+//! Use `$var:ascii` to fold accented Latin letters to ASCII, so `café` becomes
+//! `cafe` and `Größe` becomes `Grosse`. Any other non-ASCII character is replaced
+//! by `_`, or with `:ascii(drop)` dropped, or with `:ascii(escape)` written as `u`
+//! followed by its hex code point. The result must still be a valid identifier.
+//!
 //! The precise Unicode conversions are as defined by [`str::to_lowercase`] and
 //! [`str::to_uppercase`].
 //!
@@ -115,6 +139,206 @@
 //!
 //! <br>
 //!
+//! # Bindings
+//!
+//! Start the input of `paste!` with `#![paste(let name = [<`...`>])]` to give a
+//! paste a name, and write `$name` anywhere in the rest of the input instead of
+//! repeating the paste. Every `$name` is replaced by the pasted result, including
+//! inside other `[<`...`>]` and in attribute values, and the value of a binding
+//! may refer to the bindings listed before it. A plain `name` without the `$` is
+//! left alone.
+//!
+//! ```
+//! use paste::paste;
+//!
+//! # struct Point { x: i32 }
+//! #
+//! macro_rules! accessors {
+//!     ($field:ident: $t:ty) => {
+//!         paste! {
+//!             #![paste(
+//!                 let getter = [<get_ $field>],
+//!                 let setter = [<set_ $field>],
+//!             )]
+//!             impl Point {
+//!                 pub fn $getter(&self) -> $t { self.$field }
+//!                 pub fn $setter(&mut self, value: $t) { self.$field = value; }
+//!                 pub fn [<$setter _if_zero>](&mut self, value: $t) {
+//!                     if self.$getter() == 0 { self.$setter(value); }
+//!                 }
+//!             }
+//!         }
+//!     };
+//! }
+//!
+//! accessors!(x: i32);
+//! #
+//! # fn main() {
+//! #     let mut point = Point { x: 0 };
+//! #     point.set_x_if_zero(2);
+//! #     assert_eq!(point.get_x(), 2);
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//! # Lints
+//!
+//! Start the input of `paste!` with `#![paste(lints)]`, or annotate an item with
+//! `#[paste::apply(lints)]`, to be warned about case modifiers which have no
+//! effect, conversions which lose information such as `:camel` dropping the
+//! underscore before a digit, and pasted item names which do not follow the
+//! naming convention for their kind of item. The warnings are reported through
+//! the deprecation lint, which is the only way for a macro to emit a warning on
+//! stable Rust, so they need the expansion to be in item or statement position.
+//!
+//! <br>
+//!
+//! # Numeric literals
+//!
+//! A paste whose result starts with a digit produces a literal rather than an
+//! identifier, so `[<1_000 u64>]` gives `1_000u64` and `[<3.5 f32>]` gives
+//! `3.5f32`. Use `$n:u64`, `$n:f32` or any other numeric type as a modifier to
+//! set the type suffix of a literal, replacing a suffix it might already have,
+//! and `$n:unsuffixed` to strip it.
+//!
+//! <br>
+//!
+//! # Character literals
+//!
+//! Put `@char` at the start of a paste to produce a `char` literal instead of an
+//! identifier. The pasted result must be exactly one character. Use `$var:first`
+//! or `$var:last` to pick the first or last character of a segment, so
+//! `[<@char $name:first:lower>]` would paste to `'b'` if invoked with
+//! $name=`Bc`.
+//!
+//! <br>
+//!
+//! # String literals
+//!
+//! Put `@fmt` followed by a format string at the start of a paste to produce a
+//! string literal instead of an identifier. Each `{}` in the format string is
+//! replaced by the next segment, after applying its modifiers, so
+//! `[<@fmt "get_{}" $field:snake>]` would paste to `"get_user_id"` if invoked
+//! with $field=`UserId`. Use `{{` and `}}` for literal braces.
+//!
+//! <br>
+//!
+//! # Hygiene
+//!
+//! Put `@mixed_site`, `@call_site` or `@span($var)` at the start of a paste to
+//! choose where the pasted identifier resolves, instead of taking the span of the
+//! `[<`...`>]`, which depends on where the brackets were written. With
+//! `@mixed_site`, local variables and labels resolve at the definition of the
+//! macro, so a macro can introduce helper variables which never collide with
+//! user code. With `@call_site` the identifier resolves as if written at the
+//! invocation of `paste!`. With `@span($var)` it takes the span of the token passed
+//! to `$var`, so `[<@span($name) $name _len>]` declares a variable that the caller
+//! of a macro_rules macro can refer to by name. Rust has no stable def-site
+//! hygiene for items, so item names are visible to the caller with any of these.
+//!
+//! <br>
+//!
+//! # Unique names
+//!
+//! Put `@unique` at the start of a paste to generate a name private to one
+//! invocation of `paste!`, for helper items of a macro_rules macro which may be
+//! expanded many times in the same scope. `[<@unique helper>]` pastes to
+//! `__helper_` followed by a hash of the invocation, which is the same everywhere
+//! in that invocation so the helper can be referred to again, but differs from
+//! one invocation to the next. The names are not meant to be written out by
+//! hand, and may change from one build or compiler version to the next.
+//!
+//! <br>
+//!
+//! # Digests of tokens
+//!
+//! Use `@hash(...)` as a segment to paste a short hex digest of arbitrary tokens,
+//! such as a whole type, which could not be pasted otherwise. For example
+//! `[<Helper_ @hash($t)>]` pastes to a name like `Helper_0caab56d` which is the
+//! same wherever the macro is invoked with the same $t=`Vec<Option<u8>>`, and
+//! differs for other types. The digest ignores spacing, so it is stable from one
+//! build and compiler version to the next.
+//!
+//! Use `@mangle(...)` as a segment to spell out a type as an identifier, for
+//! helpers generated per instance of a generic type. Path segments and generic
+//! arguments are joined in CamelCase, so `[<@mangle($t) Helper>]` pastes to
+//! `VecOptionU8Helper` if invoked with $t=`Vec<Option<u8>>`. Tuples, arrays,
+//! slices, references and pointers are spelled out, as in `TupleU8Array4U16` for
+//! `(u8, [u16; 4])` and `RefMutSliceU8` for `&mut [u8]`, and modifiers apply to the
+//! result like to any other segment. Distinct types may occasionally spell the same
+//! identifier; use `@hash(...)` where that matters.
+//!
+//! <br>
+//!
+//! # Attribute form
+//!
+//! An item annotated with `#[paste::apply]` is expanded as if it were wrapped in
+//! `paste!`, without the extra level of nesting, so rustfmt and rust-analyzer keep
+//! working on it normally. The item still needs to parse as Rust before it reaches
+//! the macro, which means pastes can appear inside macro invocations and attribute
+//! lists but not in place of an item's own name. Use `#[paste::apply(strict)]` to
+//! make it an error for the item not to contain anything to paste.
+//!
+//! ```
+//! macro_rules! name_of {
+//!     ($name:ident) => {
+//!         #[paste::apply]
+//!         pub fn name() -> &'static str {
+//!             stringify!([<$name:snake>])
+//!         }
+//!     };
+//! }
+//!
+//! name_of!(HelloWorld);
+//! #
+//! # fn main() {
+//! #     assert_eq!(name(), "hello_world");
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//! # Associated name constants
+//!
+//! `#[derive(PasteNames)]` generates associated string constants from the same
+//! `[<`...`>]` syntax, so names of things like database tables are derived by the
+//! same rules as pasted identifiers elsewhere. Within a `#[paste(...)]` helper
+//! attribute, `$Self` refers to the name of the type. Inside `paste!` or
+//! `#[paste::apply]`, the helper attributes are left for the derive to paste, so
+//! the type itself may have a pasted name.
+//!
+//! ```
+//! use paste::PasteNames;
+//!
+//! #[derive(PasteNames)]
+//! #[paste(pub const TABLE = [<$Self:snake s>])]
+//! pub struct UserAccount;
+//! #
+//! # fn main() {
+//! #     assert_eq!(UserAccount::TABLE, "user_accounts");
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//! # Debugging
+//!
+//! Replace `paste!` by `paste::explain!` to see how each `[<`...`>]` in its input
+//! is pasted. Instead of expanding, it reports a compile error at every paste
+//! listing the segments it is made of, each modifier applied to them along with
+//! the value before and after, and the final result, which works on any
+//! toolchain without the need for `cargo expand`.
+//!
+//! When several pastes in one invocation fail, each failure is reported as its
+//! own error so that they can all be fixed in one go. This needs `paste!` to be
+//! in item or statement position. In expression position, as in
+//! `let x = paste!(...)`, Rust only takes one `compile_error!` from the
+//! expansion, so only the first failure is reported there, followed by an error
+//! that the rest of the expansion was ignored.
+//!
+//! <br>
+//!
 //! # Pasting documentation strings
 //!
 //! Within the `paste!` macro, arguments to a #\[doc ...\] attribute are
@@ -136,6 +360,32 @@
 //!
 //! method_new!(Paste);  // expands to #[doc = "Create a new `Paste` object"]
 //! ```
+//!
+//! The same goes for other attributes, at any depth of nesting, such as
+//! `#[serde(rename = "get_" $field)]`. In an attribute, a paste concatenated with
+//! string literals like `#[serde(with = "" [<$codec _serde>])]` produces a string
+//! literal, while a paste on its own like `#[derive([<$trait Impl>])]` or
+//! `#[default = [<DEFAULT_ $name>]]` produces an identifier, except in the value of
+//! a cfg predicate, which is always a string literal. Each attribute listed in a `#[cfg_attr(...)]` is expanded as if it
+//! were written standalone, after a predicate that is pasted like one in
+//! `#[cfg(...)]`.
+//!
+//! Inner attributes are expanded the same way, so a module generated inside
+//! `paste!` can document itself with `#![doc = "Bindings for " $name:snake]`.
+//! String segments may also come from `env!("...")`, as in identifiers, with the
+//! value of the variable pasted exactly as it is.
+//!
+//! To produce one line per element of a macro repetition, such as the rows of a
+//! table, use `@join` followed by a separator string and a parenthesized list of
+//! segments per line. Each line is pasted the same way as a doc string, so
+//! ``#[doc = [<@join "\n" $(("| `" $v "` | " $v:snake " |"))*>]]`` gives a row
+//! for every `$v`.
+//!
+//! Doc comments written as `///` are taken literally by default. Start the
+//! input of `paste!` with `#![paste(interpolate_docs)]`, or annotate an item
+//! with `#[paste::apply(interpolate_docs)]`, to paste each `[<`...`>]` found
+//! inside the text of a doc comment. Note that macro\_rules does not substitute
+//! `$var` inside doc comments, so those still need to be written as `#[doc = ...]`.
 
 #![doc(html_root_url = "https://docs.rs/paste/1.0.14")]
 #![allow(
@@ -149,35 +399,13 @@
 
 extern crate proc_macro;
 
-mod attr;
-mod error;
-mod segment;
-
-use crate::attr::expand_attr;
-use crate::error::{Error, Result};
-use crate::segment::Segment;
-use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
-use std::char;
-use std::iter;
-use std::panic;
+use proc_macro::TokenStream;
 
 #[proc_macro]
 pub fn paste(input: TokenStream) -> TokenStream {
-    let mut contains_paste = false;
-    let flatten_single_interpolation = true;
-    match expand(
-        input.clone(),
-        &mut contains_paste,
-        flatten_single_interpolation,
-    ) {
-        Ok(expanded) => {
-            if contains_paste {
-                expanded
-            } else {
-                input
-            }
-        }
-        Err(err) => err.to_compile_error(),
+    match paste_core::expand(input.into()) {
+        Ok(expanded) => expanded.into(),
+        Err(err) => err.to_compile_error().into(),
     }
 }
 
@@ -193,262 +421,25 @@ pub fn expr(input: TokenStream) -> TokenStream {
     paste(input)
 }
 
-fn expand(
-    input: TokenStream,
-    contains_paste: &mut bool,
-    flatten_single_interpolation: bool,
-) -> Result<TokenStream> {
-    let mut expanded = TokenStream::new();
-    let mut lookbehind = Lookbehind::Other;
-    let mut prev_none_group = None::<Group>;
-    let mut tokens = input.into_iter().peekable();
-    loop {
-        let token = tokens.next();
-        if let Some(group) = prev_none_group.take() {
-            if match (&token, tokens.peek()) {
-                (Some(TokenTree::Punct(fst)), Some(TokenTree::Punct(snd))) => {
-                    fst.as_char() == ':' && snd.as_char() == ':' && fst.spacing() == Spacing::Joint
-                }
-                _ => false,
-            } {
-                expanded.extend(group.stream());
-                *contains_paste = true;
-            } else {
-                expanded.extend(iter::once(TokenTree::Group(group)));
-            }
-        }
-        match token {
-            Some(TokenTree::Group(group)) => {
-                let delimiter = group.delimiter();
-                let content = group.stream();
-                let span = group.span();
-                if delimiter == Delimiter::Bracket && is_paste_operation(&content) {
-                    let segments = parse_bracket_as_segments(content, span)?;
-                    let pasted = segment::paste(&segments)?;
-                    let tokens = pasted_to_tokens(pasted, span)?;
-                    expanded.extend(tokens);
-                    *contains_paste = true;
-                } else if flatten_single_interpolation
-                    && delimiter == Delimiter::None
-                    && is_single_interpolation_group(&content)
-                {
-                    expanded.extend(content);
-                    *contains_paste = true;
-                } else {
-                    let mut group_contains_paste = false;
-                    let is_attribute = delimiter == Delimiter::Bracket
-                        && (lookbehind == Lookbehind::Pound || lookbehind == Lookbehind::PoundBang);
-                    let mut nested = expand(
-                        content,
-                        &mut group_contains_paste,
-                        flatten_single_interpolation && !is_attribute,
-                    )?;
-                    if is_attribute {
-                        nested = expand_attr(nested, span, &mut group_contains_paste)?;
-                    }
-                    let group = if group_contains_paste {
-                        let mut group = Group::new(delimiter, nested);
-                        group.set_span(span);
-                        *contains_paste = true;
-                        group
-                    } else {
-                        group.clone()
-                    };
-                    if delimiter != Delimiter::None {
-                        expanded.extend(iter::once(TokenTree::Group(group)));
-                    } else if lookbehind == Lookbehind::DoubleColon {
-                        expanded.extend(group.stream());
-                        *contains_paste = true;
-                    } else {
-                        prev_none_group = Some(group);
-                    }
-                }
-                lookbehind = Lookbehind::Other;
-            }
-            Some(TokenTree::Punct(punct)) => {
-                lookbehind = match punct.as_char() {
-                    ':' if lookbehind == Lookbehind::JointColon => Lookbehind::DoubleColon,
-                    ':' if punct.spacing() == Spacing::Joint => Lookbehind::JointColon,
-                    '#' => Lookbehind::Pound,
-                    '!' if lookbehind == Lookbehind::Pound => Lookbehind::PoundBang,
-                    _ => Lookbehind::Other,
-                };
-                expanded.extend(iter::once(TokenTree::Punct(punct)));
-            }
-            Some(other) => {
-                lookbehind = Lookbehind::Other;
-                expanded.extend(iter::once(other));
-            }
-            None => return Ok(expanded),
-        }
-    }
-}
-
-#[derive(PartialEq)]
-enum Lookbehind {
-    JointColon,
-    DoubleColon,
-    Pound,
-    PoundBang,
-    Other,
-}
-
-// https://github.com/dtolnay/paste/issues/26
-fn is_single_interpolation_group(input: &TokenStream) -> bool {
-    #[derive(PartialEq)]
-    enum State {
-        Init,
-        Ident,
-        Literal,
-        Apostrophe,
-        Lifetime,
-        Colon1,
-        Colon2,
-    }
-
-    let mut state = State::Init;
-    for tt in input.clone() {
-        state = match (state, &tt) {
-            (State::Init, TokenTree::Ident(_)) => State::Ident,
-            (State::Init, TokenTree::Literal(_)) => State::Literal,
-            (State::Init, TokenTree::Punct(punct)) if punct.as_char() == '\'' => State::Apostrophe,
-            (State::Apostrophe, TokenTree::Ident(_)) => State::Lifetime,
-            (State::Ident, TokenTree::Punct(punct))
-                if punct.as_char() == ':' && punct.spacing() == Spacing::Joint =>
-            {
-                State::Colon1
-            }
-            (State::Colon1, TokenTree::Punct(punct))
-                if punct.as_char() == ':' && punct.spacing() == Spacing::Alone =>
-            {
-                State::Colon2
-            }
-            (State::Colon2, TokenTree::Ident(_)) => State::Ident,
-            _ => return false,
-        };
-    }
-
-    state == State::Ident || state == State::Literal || state == State::Lifetime
-}
-
-fn is_paste_operation(input: &TokenStream) -> bool {
-    let mut tokens = input.clone().into_iter();
-
-    match &tokens.next() {
-        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {}
-        _ => return false,
-    }
-
-    let mut has_token = false;
-    loop {
-        match &tokens.next() {
-            Some(TokenTree::Punct(punct)) if punct.as_char() == '>' => {
-                return has_token && tokens.next().is_none();
-            }
-            Some(_) => has_token = true,
-            None => return false,
-        }
-    }
+/// Reports how each `[<`...`>]` in the input is pasted, segment by segment,
+/// as compile errors in place of expanding it.
+#[proc_macro]
+pub fn explain(input: TokenStream) -> TokenStream {
+    paste_core::explain(input.into()).to_compile_error().into()
 }
 
-fn parse_bracket_as_segments(input: TokenStream, scope: Span) -> Result<Vec<Segment>> {
-    let mut tokens = input.into_iter().peekable();
-
-    match &tokens.next() {
-        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {}
-        Some(wrong) => return Err(Error::new(wrong.span(), "expected `<`")),
-        None => return Err(Error::new(scope, "expected `[< ... >]`")),
-    }
-
-    let mut segments = segment::parse(&mut tokens)?;
-
-    match &tokens.next() {
-        Some(TokenTree::Punct(punct)) if punct.as_char() == '>' => {}
-        Some(wrong) => return Err(Error::new(wrong.span(), "expected `>`")),
-        None => return Err(Error::new(scope, "expected `[< ... >]`")),
-    }
-
-    if let Some(unexpected) = tokens.next() {
-        return Err(Error::new(
-            unexpected.span(),
-            "unexpected input, expected `[< ... >]`",
-        ));
+#[proc_macro_attribute]
+pub fn apply(args: TokenStream, input: TokenStream) -> TokenStream {
+    match paste_core::expand_attribute(args.into(), input.into()) {
+        Ok(expanded) => expanded.into(),
+        Err(err) => err.to_compile_error().into(),
     }
-
-    for segment in &mut segments {
-        if let Segment::String(string) = segment {
-            if string.value.starts_with("'\\u{") {
-                let hex = &string.value[4..string.value.len() - 2];
-                if let Ok(unsigned) = u32::from_str_radix(hex, 16) {
-                    if let Some(ch) = char::from_u32(unsigned) {
-                        string.value.clear();
-                        string.value.push(ch);
-                        continue;
-                    }
-                }
-            }
-            if string.value.contains(&['#', '\\', '.', '+'][..])
-                || string.value.starts_with("b'")
-                || string.value.starts_with("b\"")
-                || string.value.starts_with("br\"")
-            {
-                return Err(Error::new(string.span, "unsupported literal"));
-            }
-            let mut range = 0..string.value.len();
-            if string.value.starts_with("r\"") {
-                range.start += 2;
-                range.end -= 1;
-            } else if string.value.starts_with(&['"', '\''][..]) {
-                range.start += 1;
-                range.end -= 1;
-            }
-            string.value = string.value[range].replace('-', "_");
-        }
-    }
-
-    Ok(segments)
 }
 
-fn pasted_to_tokens(mut pasted: String, span: Span) -> Result<TokenStream> {
-    let mut tokens = TokenStream::new();
-
-    #[cfg(not(no_literal_fromstr))]
-    {
-        use proc_macro::{LexError, Literal};
-        use std::str::FromStr;
-
-        if pasted.starts_with(|ch: char| ch.is_ascii_digit()) {
-            let literal = match panic::catch_unwind(|| Literal::from_str(&pasted)) {
-                Ok(Ok(literal)) => TokenTree::Literal(literal),
-                Ok(Err(LexError { .. })) | Err(_) => {
-                    return Err(Error::new(
-                        span,
-                        &format!("`{:?}` is not a valid literal", pasted),
-                    ));
-                }
-            };
-            tokens.extend(iter::once(literal));
-            return Ok(tokens);
-        }
-    }
-
-    if pasted.starts_with('\'') {
-        let mut apostrophe = TokenTree::Punct(Punct::new('\'', Spacing::Joint));
-        apostrophe.set_span(span);
-        tokens.extend(iter::once(apostrophe));
-        pasted.remove(0);
+#[proc_macro_derive(PasteNames, attributes(paste))]
+pub fn derive_paste_names(input: TokenStream) -> TokenStream {
+    match paste_core::derive_names(input.into()) {
+        Ok(expanded) => expanded.into(),
+        Err(err) => err.to_compile_error().into(),
     }
-
-    let ident = match panic::catch_unwind(|| Ident::new(&pasted, span)) {
-        Ok(ident) => TokenTree::Ident(ident),
-        Err(_) => {
-            return Err(Error::new(
-                span,
-                &format!("`{:?}` is not a valid identifier", pasted),
-            ));
-        }
-    };
-
-    tokens.extend(iter::once(ident));
-    Ok(tokens)
 }
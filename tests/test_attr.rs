@@ -60,3 +60,137 @@ fn test_path_in_attr() {
 
     assert_eq!("foo::Bar", ty);
 }
+
+macro_rules! get_attr {
+    (#[$($attr:tt)*]) => {
+        stringify!($($attr)*).replace(char::is_whitespace, "")
+    };
+}
+
+#[test]
+fn test_nested_name_value() {
+    macro_rules! m {
+        ($y:ident) => {
+            paste! {
+                get_attr!(#[serde(rename = "x_" $y, default = "default_" $y:snake, with = a::b)])
+            }
+        };
+    }
+
+    let attr = m!(FieldName);
+
+    let expected = r#"serde(rename="x_FieldName",default="default_field_name",with=a::b)"#;
+    assert_eq!(attr, expected);
+}
+
+#[test]
+fn test_deeply_nested() {
+    let attr = paste! {
+        get_attr!(#[::a::b(c, d(e = "f" g, h(i = j "k")), "l", m = 1)])
+    };
+
+    let expected = r#"::a::b(c,d(e="fg",h(i="jk")),"l",m=1)"#;
+    assert_eq!(attr, expected);
+}
+
+#[test]
+fn test_paste_in_attr_path_and_value() {
+    paste! {
+        #[[<paste_ test>](k = "v" [<a lue>])]
+        struct E;
+    }
+
+    let _ = E;
+}
+
+#[test]
+fn test_ident_in_attr() {
+    macro_rules! m {
+        ($codec:ident, $t:ident) => {
+            paste! {
+                get_attr!(#[cfg_attr(
+                    feature = "" $codec:lower,
+                    derive([<$t Debug>]),
+                    serde(with = "" [<$codec:snake _serde>]),
+                    default = [<DEFAULT_ $codec:upper>],
+                )])
+            }
+        };
+    }
+
+    let attr = m!(MsgPack, Wire);
+
+    let expected = r#"cfg_attr(feature="msgpack",derive(WireDebug),serde(with="msg_pack_serde"),default=DEFAULT_MSGPACK,)"#;
+    assert_eq!(attr, expected);
+}
+
+#[test]
+fn test_derive_pasted() {
+    paste! {
+        #[derive([<Cl one>], [<Partial Eq>])]
+        struct [<Der ived>];
+    }
+
+    assert!(Derived.clone() == Derived);
+}
+
+#[test]
+fn test_cfg_attr() {
+    macro_rules! m {
+        ($y:ident) => {
+            paste! {
+                get_attr!(#[cfg_attr(
+                    any(feature = "x_" $y:snake, not(target_os = "" $y:lower)),
+                    doc = "foo " $y,
+                    allow(dead_code),
+                )])
+            }
+        };
+    }
+
+    let attr = m!(NoStd);
+
+    let expected = r#"cfg_attr(any(feature="x_no_std",not(target_os="nostd")),doc="fooNoStd",allow(dead_code),)"#;
+    assert_eq!(attr, expected);
+}
+
+#[test]
+fn test_cfg_attr_applied() {
+    macro_rules! m {
+        ($name:ident, $width:tt) => {
+            paste! {
+                #[cfg_attr(target_pointer_width = "" $width, derive([<De bug>]), doc = "A " $name:snake)]
+                #[cfg_attr(not(target_pointer_width = "" $width), derive(Debug))]
+                struct $name;
+            }
+        };
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    m!(Applied, 64);
+    #[cfg(not(target_pointer_width = "64"))]
+    m!(Applied, 32);
+
+    assert_eq!(format!("{:?}", Applied), "Applied");
+}
+
+#[test]
+fn test_lone_paste_in_attr_value() {
+    macro_rules! m {
+        (#[x = $x:ty]) => {
+            stringify!($x)
+        };
+    }
+
+    let ty = paste! {
+        m!(#[x = [<Foo Bar>]])
+    };
+    assert_eq!(ty, "FooBar");
+
+    paste! {
+        #[cfg(not(target_os = [<no ne>]))]
+        struct F;
+    }
+
+    let _ = F;
+}
@@ -0,0 +1,69 @@
+#![allow(clippy::let_underscore_untyped)]
+
+use paste::{paste, PasteNames};
+
+#[test]
+fn test_names() {
+    #[derive(PasteNames)]
+    #[paste(const SNAKE = [<$Self:snake>], pub const TABLE = [<Self:snake s>])]
+    #[paste(pub(crate) const UPPER = [<TBL_ $Self:snake:upper>])]
+    struct HttpRequest;
+
+    assert_eq!(HttpRequest::SNAKE, "http_request");
+    assert_eq!(HttpRequest::TABLE, "http_requests");
+    assert_eq!(HttpRequest::UPPER, "TBL_HTTP_REQUEST");
+}
+
+#[test]
+fn test_generics() {
+    #[derive(PasteNames)]
+    #[paste(const NAME = [<$Self:upper>])]
+    pub struct Wrapper<'a, T: Clone + 'a, const N: usize = 1>(#[allow(dead_code)] &'a [T; N])
+    where
+        T: Default;
+
+    #[derive(PasteNames)]
+    #[paste(const NAME = [<$Self:snake>])]
+    enum MaybeUnit<F = fn() -> u8>
+    where
+        F: Copy,
+    {
+        #[allow(dead_code)]
+        Some(F),
+    }
+
+    assert_eq!(Wrapper::<u8, 1>::NAME, "WRAPPER");
+    assert_eq!(MaybeUnit::<fn() -> u8>::NAME, "maybe_unit");
+}
+
+#[test]
+fn test_in_macro() {
+    macro_rules! table {
+        ($name:ident) => {
+            #[derive(PasteNames)]
+            #[paste(const TABLE = [<$name:snake _table>])]
+            struct $name;
+        };
+    }
+
+    table!(UserAccount);
+
+    assert_eq!(UserAccount::TABLE, "user_account_table");
+}
+
+#[test]
+fn test_in_paste() {
+    macro_rules! row {
+        ($name:ident) => {
+            paste! {
+                #[derive(PasteNames)]
+                #[paste(const TABLE = [<$name:snake s>])]
+                struct [<$name Row>];
+            }
+        };
+    }
+
+    row!(UserAccount);
+
+    assert_eq!(UserAccountRow::TABLE, "user_accounts");
+}
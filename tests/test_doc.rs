@@ -77,3 +77,166 @@ fn test_stringify() {
 
     let _ = Struct;
 }
+
+#[test]
+fn test_fmt() {
+    macro_rules! m {
+        ($ret:ident) => {
+            paste! {
+                get_doc!(#[doc = [<@fmt "Create a new `{}` object." $ret:snake>]])
+            }
+        };
+    }
+
+    let doc = m!(PasteObject);
+
+    let expected = "Create a new `paste_object` object.";
+    assert_eq!(doc, expected);
+}
+
+#[test]
+fn test_join() {
+    macro_rules! m {
+        ($($variant:ident)*) => {
+            paste! {
+                get_doc!(#[doc = "| Variant | Wire name |\n|---|---|\n" [<@join "\n" $(
+                    ("| `" $variant "` | \"" $variant:snake "\" |")
+                )*>]])
+            }
+        };
+    }
+
+    let doc = m!(Ping GetStatus);
+
+    let expected = "| Variant | Wire name |\n|---|---|\n| `Ping` | \"ping\" |\n| `GetStatus` | \"get_status\" |";
+    assert_eq!(doc, expected);
+}
+
+#[test]
+fn test_join_expr() {
+    assert_eq!(paste!([<@join ", " (a b), (r"c\" d:upper)>]), "ab, c\\D");
+    assert_eq!(paste!([<@join "\n">]), "");
+}
+
+macro_rules! get_inner_doc {
+    (#![doc = $literal:tt]) => {
+        $literal
+    };
+}
+
+#[test]
+fn test_inner_doc() {
+    macro_rules! m {
+        ($name:ident) => {
+            paste! {
+                get_inner_doc!(#![doc = "Bindings for the `" $name:snake "` service."])
+            }
+        };
+    }
+
+    let doc = m!(UserAccounts);
+
+    let expected = "Bindings for the `user_accounts` service.";
+    assert_eq!(doc, expected);
+}
+
+#[test]
+fn test_inner_doc_env() {
+    let doc = paste! {
+        get_inner_doc!(#![doc = "Generated by " env!("CARGO_PKG_NAME"):upper "."])
+    };
+
+    let expected = "Generated by PASTE.";
+    assert_eq!(doc, expected);
+}
+
+#[test]
+fn test_inner_cfg_attr() {
+    macro_rules! get_inner_cfg_attr {
+        (#![cfg_attr($pred:meta, doc = $literal:tt)]) => {
+            $literal
+        };
+    }
+
+    let doc = paste! {
+        get_inner_cfg_attr!(#![cfg_attr(all(), doc = "Module " [<Sdk Client>]:snake)])
+    };
+
+    let expected = "Module sdk_client";
+    assert_eq!(doc, expected);
+}
+
+mod test_inner_attr_in_mod {
+    use paste::paste;
+
+    macro_rules! sdk_module {
+        ($name:ident) => {
+            paste! {
+                pub mod [<$name:snake>] {
+                    #![doc = "Generated bindings for `" $name "`."]
+                    #![cfg_attr(all(), doc = "See also `" $name:snake "`.")]
+                    #![allow([<dead _code>])]
+
+                    fn unused() {}
+
+                    pub fn name() -> &'static str {
+                        stringify!($name)
+                    }
+                }
+            }
+        };
+    }
+
+    sdk_module!(PaymentsApi);
+
+    #[test]
+    fn test_inner_attr_in_mod() {
+        assert_eq!(payments_api::name(), "PaymentsApi");
+    }
+}
+
+#[test]
+fn test_interpolate_docs() {
+    let doc = paste! {
+        #![paste(interpolate_docs)]
+        get_doc!(
+            /// Returns the [<UserId:snake>] value, or "[<@fmt "{}\n" none:upper>]".
+        )
+    };
+
+    let expected = " Returns the user_id value, or \"NONE\n\".";
+    assert_eq!(doc, expected);
+}
+
+#[test]
+fn test_interpolate_docs_opt_in() {
+    let doc = paste! {
+        get_doc!(
+            /// Returns the [<UserId:snake>] value.
+        )
+    };
+
+    let expected = " Returns the [<UserId:snake>] value.";
+    assert_eq!(doc, expected);
+}
+
+#[test]
+fn test_bindings() {
+    let doc = paste! {
+        #![paste(interpolate_docs, let getter = [<get_ UserId:snake>])]
+        get_doc!(
+            /// Same as [<$getter>], or [<$getter _or_default>] with a fallback.
+        )
+    };
+
+    let expected = " Same as get_user_id, or get_user_id_or_default with a fallback.";
+    assert_eq!(doc, expected);
+
+    let doc = paste! {
+        #![paste(let getter = [<get_ UserId:snake>])]
+        get_doc!(#[doc = "Returns " $getter "."])
+    };
+
+    let expected = "Returns get_user_id.";
+    assert_eq!(doc, expected);
+}
@@ -58,6 +58,240 @@ fn test_literal_suffix() {
     assert_eq!(literal!(32), 1);
 }
 
+#[test]
+fn test_literal_suffix_modifier() {
+    macro_rules! literal {
+        ($n:literal) => {
+            paste!([<$n:u64>])
+        };
+    }
+
+    assert_eq!(literal!(1_000), 1_000u64);
+    assert_eq!(literal!(7u8), 7u64);
+    assert_eq!(std::mem::size_of_val(&literal!(0)), 8);
+    assert_eq!(std::mem::size_of_val(&paste!([<1u8:unsuffixed>])), 4);
+    assert_eq!(std::mem::size_of_val(&paste!([<0x1f:u8>])), 1);
+    assert_eq!(paste!([<0x1f32:u16>]), 0x1f32u16);
+}
+
+#[test]
+fn test_float_literal() {
+    macro_rules! float {
+        ($n:literal, $ty:ident) => {
+            paste!([<$n $ty>])
+        };
+    }
+
+    let x = float!(3.5, f32);
+    assert_eq!(std::mem::size_of_val(&x), 4);
+    assert_eq!(x, 3.5);
+
+    let y = paste!([<2.25:f64>]);
+    assert_eq!(std::mem::size_of_val(&y), 8);
+    assert_eq!(y, 2.25);
+
+    let z = paste!([<1.5f64:f32>]);
+    assert_eq!(std::mem::size_of_val(&z), 4);
+}
+
+#[test]
+fn test_char_literal() {
+    macro_rules! entry {
+        ($name:ident) => {
+            paste!((stringify!($name), [<@char $name:first>]))
+        };
+    }
+
+    assert_eq!(entry!(Alpha), ("Alpha", 'A'));
+    assert_eq!(entry!(beta), ("beta", 'b'));
+    assert_eq!(paste!([<@char omega:last:upper>]), 'A');
+    assert_eq!(paste!([<@char 'é'>]), 'é');
+    assert_eq!(paste!([<@char '\u{1F980}'>]), '\u{1F980}');
+}
+
+#[test]
+fn test_fmt() {
+    macro_rules! getter_name {
+        ($x:ident) => {
+            paste!([<@fmt "get_{}" $x:snake>])
+        };
+    }
+
+    assert_eq!(getter_name!(FooBar), "get_foo_bar");
+    assert_eq!(
+        paste!([<@fmt "{}-{}" env!("CARGO_PKG_NAME"):upper 1>]),
+        "PASTE-1"
+    );
+    assert_eq!(
+        paste!([<@fmt "{{{}}} \"{}\"\n" a "b\\c">]),
+        "{a} \"b\\c\"\n"
+    );
+    assert_eq!(paste!([<@fmt r"\{}" r#"""#>]), "\\\"");
+    assert_eq!(paste!([<@fmt "empty">]), "empty");
+    assert_eq!(paste!([<@fmt "caf\u{e9} {}" au_lait>]), "café au_lait");
+    assert_eq!(paste!([<@fmt "\u{7b}{}\u{7d}" x>]), "{x}");
+    assert_eq!(paste!([<@fmt "café {}" au_lait>]), "café au_lait");
+    assert_eq!(paste!([<@fmt r"café \{}" x>]), "café \\x");
+    assert_eq!(paste!([<@fmt r#"日本 "{}""# x>]), "日本 \"x\"");
+}
+
+#[test]
+fn test_words() {
+    macro_rules! short {
+        ($ty:ident) => {
+            paste!((
+                stringify!([<$ty:first_word:lower>]),
+                stringify!([<$ty:last_word>]),
+                stringify!([<$ty:words(1..)>]),
+                stringify!([<$ty:words(-2..=-1):snake>]),
+            ))
+        };
+    }
+
+    assert_eq!(
+        short!(HttpRequestHandler),
+        ("http", "Handler", "RequestHandler", "request_handler"),
+    );
+    assert_eq!(
+        short!(http_request_handler),
+        ("http", "handler", "request_handler", "request_handler"),
+    );
+    assert_eq!(paste!(stringify!([<AbcDefGhi:words(1)>])), "Def");
+    assert_eq!(paste!(stringify!([<AbcDefGhi:words(..-1)>])), "AbcDef");
+}
+
+#[test]
+fn test_acronyms() {
+    assert_eq!(
+        paste!(stringify!([<http_url_id:camel(HTTP, URL, ID)>])),
+        "HTTPURLID"
+    );
+    assert_eq!(
+        paste!(stringify!([<http_url_id:camel(URL, ID)>])),
+        "HttpURLID"
+    );
+    assert_eq!(
+        paste!(stringify!([<HTTPServer:snake(HTTP)>])),
+        "http_server"
+    );
+    assert_eq!(
+        paste!(stringify!([<HttpURLID:snake(URL, ID)>])),
+        "http_url_id"
+    );
+    assert_eq!(
+        paste!(stringify!([<http_server:camel(HTTP):snake(HTTP)>])),
+        "http_server",
+    );
+
+    // Words in capitals are split the same as without an acronym list.
+    assert_eq!(
+        paste!(stringify!([<FOO_BAR:camel(ID)>])),
+        paste!(stringify!([<FOO_BAR:camel>])),
+    );
+    assert_eq!(
+        paste!(stringify!([<FOO_BAR:snake(ID)>])),
+        paste!(stringify!([<FOO_BAR:snake>])),
+    );
+    assert_eq!(paste!(stringify!([<MAX_ID:camel(ID)>])), "MaxID");
+    assert_eq!(
+        paste!(stringify!([<XMLHttpRequest:snake(XML)>])),
+        "xml_http_request"
+    );
+    assert_eq!(paste!(stringify!([<valid_id:camel(ID)>])), "ValidID");
+
+    paste! {
+        #![paste(acronyms(ID))]
+        assert_eq!(stringify!([<FOO_BAR:camel>]), "FooBar");
+    }
+
+    paste! {
+        #![paste(acronyms(HTTP, URL))]
+        assert_eq!(stringify!([<http_url:camel>]), "HTTPURL");
+        assert_eq!(stringify!([<HTTPServer:snake>]), "http_server");
+        assert_eq!(stringify!([<http_url:camel(URL)>]), "HttpURL");
+    }
+}
+
+#[test]
+fn test_ascii() {
+    assert_eq!(paste!(stringify!([<café:ascii>])), "cafe");
+    assert_eq!(paste!(stringify!([<"Größe":ascii:snake>])), "grosse");
+    assert_eq!(paste!(stringify!([<"ÆSIR_Łódź":ascii>])), "AESIR_Lodz");
+    assert_eq!(paste!(stringify!([<"a日b":ascii>])), "a_b");
+    assert_eq!(paste!(stringify!([<"a日b":ascii(replace)>])), "a_b");
+    assert_eq!(paste!(stringify!([<"a日b":ascii(drop)>])), "ab");
+    assert_eq!(paste!(stringify!([<"a日b":ascii(escape)>])), "au65e5b");
+}
+
+#[test]
+fn test_hash() {
+    macro_rules! helper_name {
+        ($t:ty) => {
+            paste!(stringify!([<Helper_ @hash($t)>]))
+        };
+    }
+
+    let name = paste!(stringify!([<Helper_ @hash(Vec<Option<u8>>)>]));
+    assert_eq!(name, "Helper_0caab56d");
+    assert_eq!(helper_name!(Vec<Option<u8>>), name);
+    assert_eq!(helper_name!(Vec<Option<u8>>), name);
+    assert_ne!(helper_name!(Vec<Option<u16>>), name);
+    assert_eq!(
+        paste!(stringify!([<H @hash(Vec<Option<u8>>):upper>])),
+        "H0CAAB56D",
+    );
+}
+
+#[test]
+fn test_mangle() {
+    macro_rules! mangle {
+        ($t:ty) => {
+            paste!(stringify!([<@mangle($t)>]))
+        };
+    }
+
+    assert_eq!(mangle!(Vec<Option<u8>>), "VecOptionU8");
+    assert_eq!(mangle!(std::vec::Vec<u8>), "StdVecVecU8");
+    assert_eq!(
+        mangle!(HashMap<String, (u8, [u16; 4])>),
+        "HashMapStringTupleU8Array4U16",
+    );
+    assert_eq!(mangle!(&'a mut [u8]), "RefMutSliceU8");
+    assert_eq!(mangle!(*const ()), "PtrConstUnit");
+    assert_eq!(mangle!(fn(u8) -> bool), "FnU8ToBool");
+    assert_eq!(mangle!((u8,)), "TupleU8");
+    assert_eq!(
+        paste!(stringify!([<@mangle(Vec<Option<u8>>):upper _HELPER>])),
+        "VECOPTIONU8_HELPER",
+    );
+}
+
+#[test]
+fn test_span_selectors() {
+    macro_rules! bind_len {
+        ($name:ident) => {
+            paste! {
+                let [<@span($name) $name _len>] = stringify!($name).len();
+            }
+        };
+    }
+
+    bind_len!(abc);
+    assert_eq!(abc_len, 3);
+
+    let tmp = 1;
+    paste! {
+        let [<@mixed_site tmp>] = 2;
+        assert_eq!([<@mixed_site tmp>], 2);
+    }
+    assert_eq!(tmp, 1);
+
+    paste! {
+        let [<@call_site t mp>] = 3;
+    }
+    assert_eq!(tmp, 3);
+}
+
 #[test]
 fn test_underscore() {
     paste! {
@@ -163,6 +163,25 @@ mod test_to_camel {
     }
 }
 
+mod test_apply {
+    macro_rules! m {
+        ($name:ident) => {
+            #[paste::apply]
+            #[allow([<dead _code>])]
+            pub fn name() -> &'static str {
+                stringify!([<$name:snake>])
+            }
+        };
+    }
+
+    m!(HelloWorld);
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(name(), "hello_world");
+    }
+}
+
 mod test_doc_expr {
     // https://github.com/dtolnay/paste/issues/29
 
@@ -269,3 +288,82 @@ mod test_pat_in_expr_position {
 
     rav1e_bad!(std::fmt::Error);
 }
+
+mod test_unique {
+    use paste::paste;
+
+    macro_rules! helper {
+        ($value:expr) => {
+            paste! {
+                fn [<@unique helper>]() -> i32 {
+                    $value
+                }
+                assert_eq!([<@unique helper>](), $value);
+                assert!(stringify!([<@unique helper>]).starts_with("__helper_"));
+            }
+        };
+    }
+
+    #[test]
+    fn test() {
+        helper!(1);
+        helper!(2);
+        helper!(2);
+    }
+}
+
+mod test_bindings {
+    use paste::paste;
+
+    macro_rules! accessors {
+        ($field:ident: $ty:ty) => {
+            paste! {
+                #![paste(
+                    let getter = [<get_ $field:snake>],
+                    let setter = [<set_ $field:snake>],
+                    let swapper = [<$setter _and_ $getter>],
+                )]
+                impl Point {
+                    fn $getter(&self) -> $ty {
+                        self.$field
+                    }
+
+                    fn $setter(&mut self, value: $ty) {
+                        self.$field = value;
+                    }
+
+                    fn $swapper(&mut self, value: $ty) -> $ty {
+                        let previous = self.$getter();
+                        self.$setter(value);
+                        previous
+                    }
+                }
+            }
+        };
+    }
+
+    struct Point {
+        x: i32,
+    }
+
+    accessors!(x: i32);
+
+    #[test]
+    fn test() {
+        let mut point = Point { x: 1 };
+        point.set_x(2);
+        assert_eq!(point.set_x_and_get_x(3), 2);
+        assert_eq!(point.get_x(), 3);
+    }
+
+    #[test]
+    fn test_name_clash() {
+        let first = "caller";
+        paste! {
+            #![paste(let first = [<alpha _beta>])]
+            assert_eq!(stringify!($first), "alpha_beta");
+            assert_eq!(stringify!([<$first:first:upper>]), "A");
+            assert_eq!(first, "caller");
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use paste::paste;
+
+paste! {
+    fn [<http_url:camel()>]() {}
+}
+
+paste! {
+    fn [<http_url:snake(HTTP-URL)>]() {}
+}
+
+paste! {
+    #![paste(acronyms)]
+}
+
+paste! {
+    #![paste(acronyms(1 + 1))]
+    fn [<http_url:snake>]() {}
+}
+
+fn main() {}
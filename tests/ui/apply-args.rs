@@ -0,0 +1,10 @@
+#[paste::apply(strict)]
+fn f() {}
+
+#[paste::apply(lenient)]
+fn g() {}
+
+#[paste::apply(strict strict)]
+fn h() {}
+
+fn main() {}
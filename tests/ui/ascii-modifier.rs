@@ -0,0 +1,11 @@
+use paste::paste;
+
+paste! {
+    fn [<"café":ascii(keep)>]() {}
+}
+
+paste! {
+    fn [<"日本":ascii(drop)>]() {}
+}
+
+fn main() {}
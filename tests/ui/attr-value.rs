@@ -0,0 +1,13 @@
+use paste::paste;
+
+paste! {
+    #[doc = "a" + b]
+    struct A;
+}
+
+paste! {
+    #[serde(rename = "a" (b), default = "c")]
+    struct B;
+}
+
+fn main() {}
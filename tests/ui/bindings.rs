@@ -0,0 +1,20 @@
+use paste::paste;
+
+paste! {
+    #![paste(let = [<a b>])]
+}
+
+paste! {
+    #![paste(let name [<a b>])]
+}
+
+paste! {
+    #![paste(let name =)]
+}
+
+paste! {
+    #![paste(let name = [<a - b>])]
+    fn name() {}
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use paste::paste;
+
+paste! {
+    const C: char = [<@char a b>];
+}
+
+paste! {
+    const D: char = [<@char "":first>];
+}
+
+paste! {
+    const E: char = [<@str a>];
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use paste::paste;
+
+paste! {
+    fn [<x 1.5 z>]() {}
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+use paste::PasteNames;
+
+#[derive(PasteNames)]
+#[paste(static NAME = [<$Self>])]
+struct A;
+
+#[derive(PasteNames)]
+#[paste(const NAME = "a")]
+struct B;
+
+#[derive(PasteNames)]
+#[paste(const NAME = [<$Self:title>])]
+struct C;
+
+#[derive(PasteNames)]
+#[paste(const NAME = [<@fmt "{}_{}" $Self x>])]
+struct D;
+
+#[derive(PasteNames)]
+#[paste(const NAME = [<@unique $Self>])]
+struct E;
+
+fn main() {}
@@ -0,0 +1,17 @@
+macro_rules! getters {
+    ($($field:ident)*) => {
+        paste::explain! {
+            $(
+                #[doc = [<@fmt "Returns `{}`." $field:snake>]]
+                pub fn [<get_ $field:snake>](&self) -> u8 { 0 }
+            )*
+            const [<'a lib_ env!("CARGO_PKG_NAME"):upper>]: () = ();
+        }
+    };
+}
+
+getters!(UserId);
+
+paste::explain!();
+
+fn main() {}
@@ -0,0 +1,8 @@
+use paste::paste;
+
+fn main() {
+    let _ = paste!([<@fmt "{}_{}" a>]);
+    let _ = paste!([<@fmt "{}" a b>]);
+    let _ = paste!([<@fmt "{a}" a>]);
+    let _ = paste!([<@fmt a>]);
+}
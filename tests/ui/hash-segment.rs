@@ -0,0 +1,15 @@
+use paste::paste;
+
+paste! {
+    struct [<Helper_ @hash>];
+}
+
+paste! {
+    struct [<Helper_ @digest(u8)>];
+}
+
+paste! {
+    struct [<Helper_ @hash[u8]>];
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use paste::paste;
+
+paste! {
+    #![paste(interpolate_docs)]
+    /// The [<$name>] value.
+    pub struct A;
+}
+
+paste! {
+    #![paste(interpolate_docs)]
+    /// Unterminated [<name
+    pub struct B;
+}
+
+paste! {
+    #![paste(interpolate_doc)]
+    pub struct C;
+}
+
+fn main() {}
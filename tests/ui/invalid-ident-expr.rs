@@ -0,0 +1,5 @@
+use paste::paste;
+
+fn main() {
+    let _ = paste!([<a ' ' b>]);
+}
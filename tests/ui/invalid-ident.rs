@@ -12,4 +12,14 @@ paste! {
     fn [<f "'">]() {}
 }
 
+macro_rules! getter {
+    ($name:literal) => {
+        paste! {
+            fn [<get_ $name:lower _value>]() {}
+        }
+    };
+}
+
+getter!("Foo Bar");
+
 fn main() {}
@@ -0,0 +1,7 @@
+use paste::paste;
+
+fn main() {
+    let _ = paste!([<@join "\n" a>]);
+    let _ = paste!([<@join>]);
+    let _ = paste!([<@join "\n" ('a)>]);
+}
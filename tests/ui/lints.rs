@@ -0,0 +1,18 @@
+#![deny(deprecated)]
+#![allow(dead_code, non_snake_case, non_upper_case_globals)]
+
+use paste::paste;
+
+paste! {
+    #![paste(lints)]
+
+    struct [<Foo Bar:camel>];
+
+    fn [<get_ field_1:camel>]() {}
+
+    const [<HTTPServer:snake:upper>]: () = ();
+
+    static [<limit>]: u8 = 0;
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use paste::paste;
+
+paste! {
+    struct [<Helper_ @mangle()>];
+}
+
+paste! {
+    struct [<Helper_ @mangle(<>)>];
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use paste::paste;
+
+fn main() {
+    paste! {
+        let [<@mixed_site hidden>] = 1;
+    }
+    let _ = hidden;
+}
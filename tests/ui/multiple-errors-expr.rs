@@ -0,0 +1,6 @@
+use paste::paste;
+
+fn main() {
+    // Only the first error is reported in expression position.
+    let _ = paste!(([<a:bogus>], [<b:bogus2>]));
+}
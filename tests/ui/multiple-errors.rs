@@ -0,0 +1,14 @@
+use paste::paste;
+
+paste! {
+    fn [<a ' ' b>]() {}
+
+    fn [<c:bogus>]() {}
+
+    #[cfg_attr(feature = [<@char ab>], doc = [<@fmt "{}">])]
+    mod m {
+        struct [<'a 'b>];
+    }
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use paste::paste;
+
+paste! {
+    fn [<@span a>]() {}
+}
+
+paste! {
+    fn [<@span() a>]() {}
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use paste::paste;
+
+paste! {
+    const N: u8 = [<x:u8>];
+}
+
+fn main() {}
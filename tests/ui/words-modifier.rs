@@ -0,0 +1,23 @@
+use paste::paste;
+
+paste! {
+    fn [<AbcDef:words(3..)>]() {}
+}
+
+paste! {
+    fn [<AbcDef:words(a)>]() {}
+}
+
+paste! {
+    fn [<AbcDef:words>]() {}
+}
+
+paste! {
+    fn [<AbcDef:lower(1)>]() {}
+}
+
+paste! {
+    fn [<___:first_word>]() {}
+}
+
+fn main() {}